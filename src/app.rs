@@ -1,22 +1,47 @@
 use crate::{
+    config::Config,
     editor::EditorMode,
-    term::{self, TerminalInterface},
-    ui::UserInterface,
+    term::{self, FRAME_RATE, TICK_RATE, TerminalInterface},
+    ui::{UiAction, UserInterface},
 };
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::Position;
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+    time::{SystemTime, UNIX_EPOCH},
+};
 // use std::sync::Arc;
 // use ticks::TickTick;
 use tokio::sync::mpsc::{self, UnboundedSender};
 // use tui_text::EditorMode;
 
+const MOUSE_SCROLL_LINES: i16 = 3;
+
+const MIN_RATE: f64 = 0.1;
+const MAX_RATE: f64 = 1000.0;
+
+// Reads a positive rate from the environment, e.g. TICKTUI_FRAME_RATE=30. Rates are clamped
+// since a huge one rounds the interval down to zero, which tokio refuses
+fn rate_from_env(name: &str, default: f64) -> f64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+        .map(|rate| rate.clamp(MIN_RATE, MAX_RATE))
+        .unwrap_or(default)
+}
+
 enum Action {
     Tick,
     Render,
     Resize(u16, u16),
-    Click(Position),
+    Ui(UiAction),
+    EditExternally,
     Quit,
     // Error(String),
     // RefreshTasks,
@@ -28,6 +53,7 @@ pub struct TickTui {
     ti: TerminalInterface,
     ui: UserInterface,
     quitting: bool,
+    dirty: bool,
 }
 
 impl TickTui {
@@ -44,15 +70,25 @@ impl TickTui {
     // }
 
     pub fn new() -> Result<Self> {
-        let ti = TerminalInterface::new()?;
-        let ui = UserInterface::new();
+        let ti = TerminalInterface::new()?
+            .with_tick_rate(rate_from_env("TICKTUI_TICK_RATE", TICK_RATE))
+            .with_frame_rate(rate_from_env("TICKTUI_FRAME_RATE", FRAME_RATE));
+        let config = Config::load();
+        let ui = UserInterface::new(&config);
         let quitting = false;
-        Ok(Self { ti, ui, quitting })
+        let dirty = true;
+        Ok(Self {
+            ti,
+            ui,
+            quitting,
+            dirty,
+        })
     }
 
     pub async fn run(&mut self) -> Result<()> {
         let (tx, mut rx) = mpsc::unbounded_channel();
         self.ti.enter()?;
+        tracing::info!("entered terminal interface");
 
         loop {
             if let Some(event) = self.ti.next().await {
@@ -69,6 +105,7 @@ impl TickTui {
         }
 
         self.ti.exit()?;
+        tracing::info!("exited terminal interface");
         Ok(())
     }
 
@@ -88,18 +125,40 @@ impl TickTui {
 
     fn handle_key_event(&mut self, key: KeyEvent, tx: &UnboundedSender<Action>) -> Result<()> {
         match key.code {
+            KeyCode::Char('q')
+                if self.ui.has_pending_action()
+                    || self.ui.has_overlay()
+                    || self.ui.q_records_macros() =>
+            {
+                self.send_ui_key_event(key, tx)?
+            }
             KeyCode::Char('q') => match self.ui.get_composite_editor_mode() {
                 Some(EditorMode::Normal) => tx.send(Action::Quit)?,
                 None => match self.ui.get_main_editor_mode() {
                     EditorMode::Normal => tx.send(Action::Quit)?,
-                    _ => self.ui.handle_key_event(key),
+                    _ => self.send_ui_key_event(key, tx)?,
                 },
-                _ => self.ui.handle_key_event(key),
+                _ => self.send_ui_key_event(key, tx)?,
             },
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 tx.send(Action::Quit)?
             }
-            _ => self.ui.handle_key_event(key),
+            KeyCode::Char('e')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !self.ui.has_pending_action()
+                    && !self.ui.has_overlay()
+                    && self.ui.active_editor_mode() == Some(EditorMode::Normal) =>
+            {
+                tx.send(Action::EditExternally)?
+            }
+            _ => self.send_ui_key_event(key, tx)?,
+        }
+        Ok(())
+    }
+
+    fn send_ui_key_event(&mut self, key: KeyEvent, tx: &UnboundedSender<Action>) -> Result<()> {
+        if let Some(action) = self.ui.map_key_event(key) {
+            tx.send(Action::Ui(action))?;
         }
         Ok(())
     }
@@ -111,12 +170,26 @@ impl TickTui {
     ) -> Result<()> {
         match mouse.kind {
             MouseEventKind::Down(button) => match button {
-                MouseButton::Left => tx.send(Action::Click(Position {
+                MouseButton::Left => tx.send(Action::Ui(UiAction::Click(Position {
                     x: mouse.column,
                     y: mouse.row,
-                }))?,
+                })))?,
                 _ => {}
             },
+            MouseEventKind::ScrollUp => tx.send(Action::Ui(UiAction::Scroll(
+                Position {
+                    x: mouse.column,
+                    y: mouse.row,
+                },
+                -MOUSE_SCROLL_LINES,
+            )))?,
+            MouseEventKind::ScrollDown => tx.send(Action::Ui(UiAction::Scroll(
+                Position {
+                    x: mouse.column,
+                    y: mouse.row,
+                },
+                MOUSE_SCROLL_LINES,
+            )))?,
             _ => {}
         }
         Ok(())
@@ -124,10 +197,19 @@ impl TickTui {
 
     fn update(&mut self, action: Action) -> Result<()> {
         match action {
-            Action::Tick => {}
-            Action::Render => self.render()?,
-            Action::Resize(w, h) => self.ti.resize(w, h)?,
-            Action::Click(pos) => self.ui.handle_mouse_click(pos),
+            Action::Tick => self.dirty |= self.ui.tick(),
+            Action::Render if self.dirty => self.render()?,
+            Action::Render => {}
+            Action::Resize(w, h) => {
+                tracing::debug!(w, h, "resize");
+                self.dirty = true;
+                self.ti.resize(w, h)?
+            }
+            Action::Ui(action) => {
+                self.dirty = true;
+                self.ui.execute_action(action)
+            }
+            Action::EditExternally => self.edit_externally()?,
             Action::Quit => self.quitting = true,
             // Action::Error(msg) => self.error(msg),
             // _ => {}
@@ -139,8 +221,72 @@ impl TickTui {
         self.ti.draw(|f| {
             let _ = self.ui.draw(f, f.area());
         })?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    // Suspends the TUI while the active field is edited in $VISUAL or $EDITOR
+    fn edit_externally(&mut self) -> Result<()> {
+        let Some(text) = self.ui.get_active_text() else {
+            return Ok(());
+        };
+        let path = temp_file_path();
+        // create_new refuses to follow a file or symlink already planted at the path
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(text.as_bytes()));
+        if let Err(e) = written {
+            tracing::error!("failed to create {}: {}", path.display(), e);
+            return Ok(());
+        }
+        self.ti.exit()?;
+        let status = run_external_editor(&path);
+        self.ti.enter()?;
+        self.ti.clear()?;
+        self.dirty = true;
+        let edited = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        match (status, edited) {
+            (Ok(status), _) if !status.success() => {
+                tracing::warn!("external editor exited with {}", status)
+            }
+            (Ok(_), Ok(edited)) => {
+                // Most editors end the file with a newline the field didn't have
+                let edited = edited.replace("\r\n", "\n");
+                let edited = edited.strip_suffix('\n').unwrap_or(&edited);
+                if edited != text {
+                    self.ui.set_active_text(edited);
+                }
+            }
+            (Ok(_), Err(e)) => tracing::error!("failed to read {}: {}", path.display(), e),
+            (Err(e), _) => tracing::error!("failed to run external editor: {}", e),
+        }
         Ok(())
     }
 
     // fn error(&mut self, _message: String) {}
 }
+
+// A fresh name each time, so a file left behind by an earlier edit is never reused
+fn temp_file_path() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("ticktui-{}-{}.md", std::process::id(), nanos))
+}
+
+// Runs the editor named by $VISUAL or $EDITOR, falling back to vi. The variable may carry
+// arguments, e.g. EDITOR="code --wait"
+fn run_external_editor(path: &Path) -> std::io::Result<ExitStatus> {
+    let command = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    Command::new(program).args(parts).arg(path).status()
+}
@@ -65,7 +65,8 @@ pub async fn perform_authorization(
     let auth_result = Authorization::begin_auth(client_id.clone(), redirect_uri.clone());
     let awaiting_auth = match auth_result {
         Ok(auth) => auth,
-        Err(_e) => {
+        Err(e) => {
+            tracing::error!("failed to begin authorization: {:?}", e);
             return None;
         }
     };
@@ -80,6 +81,7 @@ pub async fn perform_authorization(
         let state_storage = auth_state_clone.clone();
         async move {
             if let Some(error) = callback.error {
+                tracing::warn!("authorization callback returned an error: {}", error);
                 return (
                     StatusCode::BAD_REQUEST,
                     Html(format!(
@@ -108,7 +110,8 @@ pub async fn perform_authorization(
     let app = Router::new().route("/callback", get(callback_handler));
     let listener = match tokio::net::TcpListener::bind("127.0.0.1:8080").await {
         Ok(l) => l,
-        Err(_e) => {
+        Err(e) => {
+            tracing::error!("failed to bind callback listener: {:?}", e);
             return None;
         }
     };
@@ -124,6 +127,7 @@ pub async fn perform_authorization(
             break code;
         }
         if start.elapsed() > timeout {
+            tracing::warn!("timed out waiting for authorization callback");
             return None;
         }
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -133,18 +137,27 @@ pub async fn perform_authorization(
         .finish_auth(client_secret, received_code, received_state)
         .await
     {
-        Ok(token) => Some(token),
-        Err(_e) => None,
+        Ok(token) => {
+            tracing::info!("authorization succeeded");
+            Some(token)
+        }
+        Err(e) => {
+            tracing::error!("failed to finish authorization: {:?}", e);
+            None
+        }
     }
 }
 
 pub async fn get_access_token(client_id: String, client_secret: String) -> Option<AccessToken> {
     if let Some(token) = load_cached_token() {
+        tracing::debug!("using cached access token");
         return Some(token);
     }
     match perform_authorization(client_id, client_secret).await {
         Some(token) => {
-            let _ = save_token_cache(&token);
+            if let Err(e) = save_token_cache(&token) {
+                tracing::warn!("failed to cache access token: {}", e);
+            }
             Some(token)
         }
         None => None,
@@ -0,0 +1,185 @@
+use chrono::{NaiveDate, NaiveTime};
+use serde::Deserialize;
+use std::{fmt::Write, path::PathBuf};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub dates: DateConfig,
+    pub layout: LayoutConfig,
+    pub editor: EditorConfig,
+    pub spellcheck: SpellConfig,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct EditorConfig {
+    // The line width gq reflows to
+    pub text_width: usize,
+    // Whether the main editor starts with soft wrap on, zw toggles it
+    pub wrap: bool,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            text_width: 80,
+            wrap: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SpellConfig {
+    // Underlines misspelled words in the main editor, z= suggests replacements
+    pub enabled: bool,
+    // Names the word list, ticktui/dictionaries/<language>.txt in the config dir
+    pub language: String,
+    // Words never flagged, compared without case
+    pub ignore: Vec<String>,
+}
+
+impl Default for SpellConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            language: "en_US".to_string(),
+            ignore: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DateConfig {
+    pub date_format: String,
+    pub clock_24h: bool,
+    pub week_start: WeekStart,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Sunday,
+    Monday,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    pub min_width: u16,
+    pub min_height: u16,
+    pub narrow_width: u16,
+    // Relative widths of the content, main editor and composite editor panes
+    pub pane_ratios: [u16; 3],
+    // 0 means no cap
+    pub max_width: u16,
+    pub max_height: u16,
+    pub reverse_panes: bool,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            min_width: 40,
+            min_height: 12,
+            narrow_width: 80,
+            pane_ratios: [20, 40, 40],
+            max_width: 0,
+            max_height: 0,
+            reverse_panes: false,
+        }
+    }
+}
+
+impl Default for DateConfig {
+    fn default() -> Self {
+        Self {
+            date_format: "%m/%d/%Y".to_string(),
+            clock_24h: false,
+            week_start: WeekStart::Sunday,
+        }
+    }
+}
+
+impl DateConfig {
+    pub fn time_format(&self) -> &'static str {
+        if self.clock_24h { "%H:%M" } else { "%I:%M %p" }
+    }
+
+    // A sample date in the configured format, for placeholders and error messages
+    pub fn date_example(&self) -> String {
+        NaiveDate::from_ymd_opt(2025, 3, 14)
+            .map(|date| date.format(&self.date_format).to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn time_example(&self) -> String {
+        NaiveTime::from_hms_opt(9, 30, 0)
+            .map(|time| time.format(self.time_format()).to_string())
+            .unwrap_or_default()
+    }
+}
+
+fn get_config_path() -> Option<PathBuf> {
+    let mut path = dirs::config_dir()?;
+    path.push("ticktui");
+    path.push("config.json");
+    Some(path)
+}
+
+impl Config {
+    // Falls back to the defaults when the file is missing or can't be parsed
+    pub fn load() -> Self {
+        let Some(path) = get_config_path() else {
+            return Self::default();
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        let mut config: Self = match serde_json::from_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!("failed to parse {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+        if !is_valid_date_format(&config.dates.date_format) {
+            tracing::warn!("invalid date_format {:?}", config.dates.date_format);
+            config.dates.date_format = DateConfig::default().date_format;
+        }
+        if config.editor.text_width == 0 {
+            tracing::warn!("text_width must be at least 1");
+            config.editor.text_width = EditorConfig::default().text_width;
+        }
+        // A cap below the minimum size would only ever show the too small placeholder
+        let layout = &mut config.layout;
+        if layout.max_width != 0 {
+            layout.max_width = layout.max_width.max(layout.min_width);
+        }
+        if layout.max_height != 0 {
+            layout.max_height = layout.max_height.max(layout.min_height);
+        }
+        if config.layout.pane_ratios.iter().all(|ratio| *ratio == 0) {
+            tracing::warn!("pane_ratios are all zero");
+            config.layout.pane_ratios = LayoutConfig::default().pane_ratios;
+        }
+        config
+    }
+}
+
+// chrono panics when displaying a format a date can't fill, like a bad specifier or %H, so
+// formats are checked up front. The text also has to parse back to the same date, or the
+// validator would reject every date the picker writes
+fn is_valid_date_format(format: &str) -> bool {
+    let Some(sample) = NaiveDate::from_ymd_opt(2025, 3, 14) else {
+        return false;
+    };
+    let mut text = String::new();
+    if write!(text, "{}", sample.format(format)).is_err() {
+        return false;
+    }
+    NaiveDate::parse_from_str(&text, format) == Ok(sample)
+}
@@ -0,0 +1,156 @@
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::Widget,
+};
+
+use crate::{config::WeekStart, popup::Popup};
+
+const WEEKDAYS: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+const PICKER_WIDTH: u16 = 24;
+const PICKER_HEIGHT: u16 = 12;
+
+#[derive(Debug, Clone, Copy)]
+pub enum DatePickerAction {
+    Days(i64),
+    Months(i32),
+    Today,
+    Tomorrow,
+    NextWeek,
+}
+
+pub struct DatePicker {
+    selected: NaiveDate,
+    today: NaiveDate,
+    format: String,
+    week_start: WeekStart,
+}
+
+impl DatePicker {
+    // Starts on the date already in the field, or today if it doesn't parse
+    pub fn new(text: &str, format: &str, week_start: WeekStart) -> Self {
+        let today = Local::now().date_naive();
+        let selected = NaiveDate::parse_from_str(text.trim(), format).unwrap_or(today);
+        Self {
+            selected,
+            today,
+            format: format.to_string(),
+            week_start,
+        }
+    }
+
+    // The selected date in the field's format
+    pub fn formatted(&self) -> String {
+        self.selected.format(&self.format).to_string()
+    }
+
+    fn weekday_offset(&self, date: NaiveDate) -> u32 {
+        match self.week_start {
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+        }
+    }
+
+    // Day names starting from the configured first day of the week
+    fn weekday_header(&self) -> String {
+        let mut days = WEEKDAYS;
+        if let WeekStart::Monday = self.week_start {
+            days.rotate_left(1);
+        }
+        days.join(" ")
+    }
+
+    pub fn apply(&mut self, action: DatePickerAction) {
+        let moved = match action {
+            DatePickerAction::Days(days) => self.selected.checked_add_signed(Duration::days(days)),
+            DatePickerAction::Months(months) if months < 0 => self
+                .selected
+                .checked_sub_months(Months::new(months.unsigned_abs())),
+            DatePickerAction::Months(months) => {
+                self.selected.checked_add_months(Months::new(months as u32))
+            }
+            DatePickerAction::Today => Some(self.today),
+            DatePickerAction::Tomorrow => self.today.succ_opt(),
+            DatePickerAction::NextWeek => self.today.checked_add_signed(Duration::weeks(1)),
+        };
+        if let Some(date) = moved {
+            self.selected = date;
+        }
+    }
+
+    fn month_text(&self) -> Text<'static> {
+        let first = self.selected.with_day(1).unwrap_or(self.selected);
+        let days_in_month = first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .map(|last| last.day())
+            .unwrap_or(31);
+        let mut lines = vec![
+            Line::from(first.format("%B %Y").to_string()).centered(),
+            Line::from(self.weekday_header()),
+        ];
+        let mut week = vec![Span::raw("   ".repeat(self.weekday_offset(first) as usize))];
+        for day in 1..=days_in_month {
+            let mut style = Style::default();
+            if day == self.selected.day() {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            if first.with_day(day) == Some(self.today) {
+                style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            }
+            week.push(Span::styled(format!("{:>2}", day), style));
+            week.push(Span::raw(" "));
+            if first.with_day(day).map(|date| self.weekday_offset(date)) == Some(6) {
+                lines.push(Line::from(std::mem::take(&mut week)));
+            }
+        }
+        if !week.is_empty() {
+            lines.push(Line::from(week));
+        }
+        Text::from(lines)
+    }
+}
+
+impl Widget for &DatePicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Popup::new(self.month_text())
+            .with_title("Pick a date")
+            .with_fixed_size(PICKER_WIDTH, PICKER_HEIGHT)
+            .with_wrap(false)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(picker: &DatePicker) -> Vec<String> {
+        let text = picker.month_text();
+        text.lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn month_starts_weeks_on_monday() {
+        // September 2025 begins on a Monday and ends on a Tuesday
+        let picker = DatePicker::new("09/15/2025", "%m/%d/%Y", WeekStart::Monday);
+        let rows = rows(&picker);
+        assert_eq!(rows[1], "Mo Tu We Th Fr Sa Su");
+        assert_eq!(rows[2].trim_end(), " 1  2  3  4  5  6  7");
+        assert_eq!(rows[3].trim_end(), " 8  9 10 11 12 13 14");
+        assert_eq!(rows[6].trim_end(), "29 30");
+        assert_eq!(rows.len(), 7);
+    }
+
+    #[test]
+    fn month_starts_weeks_on_sunday() {
+        let picker = DatePicker::new("09/15/2025", "%m/%d/%Y", WeekStart::Sunday);
+        let rows = rows(&picker);
+        assert_eq!(rows[1], "Su Mo Tu We Th Fr Sa");
+        assert_eq!(rows[2].trim_end(), "    1  2  3  4  5  6");
+        assert_eq!(rows[3].trim_end(), " 7  8  9 10 11 12 13");
+    }
+}
@@ -1,6 +1,13 @@
 use tui_textarea::{CursorMove, Input};
 
-use super::{EditorMode, TextObject, TextObjectModifier};
+use super::{BlockEdge, CharSearch, EditorMode, TextObject, TextObjectModifier};
+
+#[derive(Debug, Clone, Copy)]
+pub enum EditorOperator {
+    Delete,
+    Change,
+    Yank,
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum EditorPendingAction {
@@ -9,6 +16,19 @@ pub enum EditorPendingAction {
     Select(Option<TextObjectModifier>),
     Yank(Option<TextObjectModifier>),
     ReplaceChar,
+    FindChar(CharSearch, Option<EditorOperator>),
+    Register,
+    Search,
+    Goto,
+    VisualGoto,
+    Scroll,
+    SetMark,
+    JumpToMark(bool),
+    Indent,
+    Dedent,
+    Record,
+    PlayMacro,
+    Reflow(Option<TextObjectModifier>),
     // Command(Option<EditorCommand>),
 }
 
@@ -16,16 +36,43 @@ pub enum EditorPendingAction {
 pub enum EditorAction {
     SetMode(EditorMode),
     MoveCursor(CursorMove),
+    FindChar(CharSearch, char),
+    RepeatFind(bool),
     Insert(TextObject),
+    InsertBlock(BlockEdge),
     ApplyInput(Input),
     Delete(TextObject),
     Select(TextObject),
     Yank(TextObject),
     ReplaceChar(char),
+    Overwrite(char),
+    RestoreReplaced,
+    JoinLines(TextObject),
+    Indent(TextObject),
+    Dedent(TextObject),
     // Copy,
     Paste,
     Undo,
     Redo,
+    RepeatChange,
+    SelectRegister(char),
+    Reflow(TextObject),
+    Record,
+    StartRecording(char),
+    PlayMacro(char),
+    SetMark(char),
+    JumpToMark(char, bool),
+    JumpBack,
+    JumpForward,
+    StartSearch,
+    SearchInput(Input),
+    SearchNext,
+    SearchPrev,
+    OpenLinks,
+    ShowRegisters,
+    SuggestSpelling,
+    ScrollColumns(i16),
+    ToggleWrap,
     // Command(EditorCommand),
     Pending(EditorPendingAction),
     MultiAction(Vec<EditorAction>),
@@ -7,13 +7,26 @@ use tui_textarea::CursorMove;
 
 use crate::editor::EditorStyle;
 
-use super::{Editor, EditorAction, EditorActions, EditorMode, EditorPendingAction};
+use super::{
+    Editor, EditorAction, EditorActions, EditorMode, EditorPendingAction, FieldPicker,
+    MacroRecorder,
+};
+
+#[derive(Debug, Clone, Copy)]
+enum FieldEdge {
+    Top,
+    Bottom,
+}
+
 #[allow(dead_code)]
 pub struct CompositeEditor {
     editors: Vec<Editor>,
     active_index: Option<usize>,
     constraints: Vec<Constraint>,
     last_area: Option<Rect>,
+    macros: MacroRecorder,
+    // Whether any field changed since the last save
+    modified: bool,
 }
 
 #[allow(dead_code)]
@@ -25,6 +38,8 @@ impl CompositeEditor {
             active_index,
             constraints,
             last_area: None,
+            macros: MacroRecorder::default(),
+            modified: false,
         };
         composite.set_active_editor(active_index);
         composite
@@ -52,6 +67,60 @@ impl CompositeEditor {
             .map(|editor| editor.get_mode())
     }
 
+    pub fn get_active_picker(&self) -> Option<FieldPicker> {
+        self.active_index
+            .and_then(|index| self.editors.get(index))
+            .and_then(|editor| editor.get_picker())
+    }
+
+    pub fn get_active_text(&self) -> Option<String> {
+        self.active_index
+            .and_then(|index| self.editors.get(index))
+            .map(|editor| editor.get_lines().join("\n"))
+    }
+
+    // Registers are kept per field, so this lists the active field's
+    pub fn register_lines(&self) -> Vec<String> {
+        self.active_index
+            .and_then(|index| self.editors.get(index))
+            .map(|editor| editor.register_lines())
+            .unwrap_or_default()
+    }
+
+    // Replaces the active field's text, as a picker or external editor does
+    pub fn set_active_text(&mut self, text: &str) {
+        let before = self.get_text();
+        if let Some(editor) = self.get_active_editor() {
+            editor.set_text(text);
+        }
+        self.modified |= self.get_text() != before;
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    // Saving is blocked while any field is invalid. The first invalid field gets the focus so
+    // the user lands on what needs fixing
+    pub fn save(&mut self) -> Result<(), String> {
+        if !self.is_valid() {
+            let invalid = self.editors.iter().position(|editor| !editor.is_valid());
+            self.set_active_editor(invalid);
+            return Err(self.validation_error().unwrap_or_default());
+        }
+        self.modified = false;
+        Ok(())
+    }
+
+    // The text of every field, one field after another
+    pub fn get_text(&self) -> String {
+        self.editors
+            .iter()
+            .map(|editor| editor.get_lines().join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn set_last_area(&mut self, area: Rect) {
         self.last_area = Some(area);
     }
@@ -81,6 +150,35 @@ impl CompositeEditor {
         }
     }
 
+    pub fn on_scroll(&mut self, pos: Position, delta: i16) {
+        if let Some(area) = self.last_area {
+            let chunks = self.create_chunks(area);
+            for (i, chunk) in chunks.iter().enumerate() {
+                if chunk.contains(pos) {
+                    if let Some(editor) = self.editors.get_mut(i) {
+                        editor.on_scroll(delta);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.editors.iter().all(|editor| editor.is_valid())
+    }
+
+    // The error of the first invalid field, prefixed with its title
+    pub fn validation_error(&self) -> Option<String> {
+        self.editors.iter().find_map(|editor| {
+            let error = editor.validation_error()?;
+            Some(match editor.get_title() {
+                Some(title) => format!("{}: {}", title, error),
+                None => error,
+            })
+        })
+    }
+
     pub fn is_cursor_at_line_start(&mut self) -> bool {
         if let Some(editor) = self.get_active_editor() {
             editor.is_cursor_at_line_start()
@@ -110,15 +208,40 @@ impl CompositeEditor {
 
 impl EditorActions for CompositeEditor {
     fn execute_action(&mut self, action: EditorAction) {
+        // Macros are recorded here rather than per field so they can move between fields
+        match action {
+            EditorAction::Record if self.macros.is_recording() => {
+                self.macros.stop();
+                return;
+            }
+            EditorAction::Record => {
+                self.set_pending_action(Some(EditorPendingAction::Record));
+                return;
+            }
+            EditorAction::StartRecording(register) => {
+                self.set_pending_action(None);
+                self.macros.start(register);
+                return;
+            }
+            EditorAction::PlayMacro(register) => {
+                self.set_pending_action(None);
+                for action in self.macros.get(register) {
+                    self.execute_action(action);
+                }
+                return;
+            }
+            _ => self.macros.record(&action),
+        }
+        let before = self.get_text();
         if let Some(active_index) = self.active_index {
             let num_editors = self.editors.len();
             let mut cursor_movement = None;
+            let mut field_entry = None;
             if let Some(editor) = self.get_active_editor() {
                 match action {
                     EditorAction::MoveCursor(CursorMove::Up) => match editor.get_cursor_pos() {
                         (row, _col) if row == 0 && active_index > 0 => {
-                            cursor_movement =
-                                Some((editor.get_desired_column(), CursorMove::Bottom));
+                            field_entry = Some((editor.get_desired_column(), FieldEdge::Bottom));
                             self.set_active_editor(Some(active_index - 1));
                         }
                         _ => editor.execute_action(action),
@@ -128,7 +251,7 @@ impl EditorActions for CompositeEditor {
                             if row >= editor.get_lines().len().saturating_sub(1)
                                 && active_index + 1 < num_editors =>
                         {
-                            cursor_movement = Some((editor.get_desired_column(), CursorMove::Top));
+                            field_entry = Some((editor.get_desired_column(), FieldEdge::Top));
                             self.set_active_editor(Some(active_index + 1));
                         }
                         _ => editor.execute_action(action),
@@ -158,7 +281,18 @@ impl EditorActions for CompositeEditor {
                     editor.execute_action(EditorAction::MoveCursor(movement));
                 }
             }
+            // Entering a field from above or below keeps the column the cursor started from
+            if let Some((col, edge)) = field_entry
+                && let Some(editor) = self.get_active_editor()
+            {
+                let row = match edge {
+                    FieldEdge::Top => 0,
+                    FieldEdge::Bottom => editor.get_lines().len().saturating_sub(1),
+                };
+                editor.jump_to_row(row, col);
+            }
         }
+        self.modified |= self.get_text() != before;
     }
 
     fn set_pending_action(&mut self, pending: Option<EditorPendingAction>) {
@@ -196,3 +330,84 @@ impl WidgetRef for CompositeEditor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::{TextObject, date_validator};
+
+    // A short field between two long ones
+    fn fields() -> CompositeEditor {
+        let editors = vec![
+            Editor::default().with_content("long field one"),
+            Editor::default().with_content("ab"),
+            Editor::default().with_content("long field two"),
+        ];
+        CompositeEditor::new(editors, vec![Constraint::Length(3); 3])
+    }
+
+    fn cursor(composite: &mut CompositeEditor) -> (usize, usize) {
+        composite
+            .get_active_editor()
+            .map(|editor| editor.get_cursor_pos())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn down_through_short_field_keeps_column() {
+        let mut composite = fields();
+        if let Some(editor) = composite.get_active_editor() {
+            editor.jump_to_row(0, 8);
+        }
+        composite.execute_action(EditorAction::MoveCursor(CursorMove::Down));
+        assert_eq!(composite.active_index, Some(1));
+        assert_eq!(cursor(&mut composite), (0, 1));
+        composite.execute_action(EditorAction::MoveCursor(CursorMove::Down));
+        assert_eq!(composite.active_index, Some(2));
+        assert_eq!(cursor(&mut composite), (0, 8));
+    }
+
+    #[test]
+    fn up_through_short_field_keeps_column() {
+        let mut composite = fields();
+        composite.set_active_editor(Some(2));
+        if let Some(editor) = composite.get_active_editor() {
+            editor.jump_to_row(0, 8);
+        }
+        composite.execute_action(EditorAction::MoveCursor(CursorMove::Up));
+        assert_eq!(composite.active_index, Some(1));
+        assert_eq!(cursor(&mut composite), (0, 1));
+        composite.execute_action(EditorAction::MoveCursor(CursorMove::Up));
+        assert_eq!(composite.active_index, Some(0));
+        assert_eq!(cursor(&mut composite), (0, 8));
+    }
+
+    #[test]
+    fn edits_mark_modified_until_saved() {
+        let mut composite = fields();
+        composite.execute_action(EditorAction::MoveCursor(CursorMove::Down));
+        assert!(!composite.is_modified());
+        composite.execute_action(EditorAction::Delete(TextObject::Char));
+        assert!(composite.is_modified());
+        assert_eq!(composite.save(), Ok(()));
+        assert!(!composite.is_modified());
+        composite.set_active_text("cd");
+        assert!(composite.is_modified());
+    }
+
+    #[test]
+    fn invalid_field_blocks_saving() {
+        let editors = vec![
+            Editor::default().with_content("notes"),
+            Editor::default()
+                .with_title("Date")
+                .with_content("13/45/2025")
+                .with_validator(date_validator("%m/%d/%Y", "03/14/2025")),
+        ];
+        let mut composite = CompositeEditor::new(editors, vec![Constraint::Length(3); 2]);
+        composite.set_active_text("changed");
+        assert_eq!(composite.save(), Err("Date: No such date".to_string()));
+        assert_eq!(composite.active_index, Some(1));
+        assert!(composite.is_modified());
+    }
+}
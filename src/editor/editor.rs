@@ -1,23 +1,83 @@
+use std::{
+    cell::Cell,
+    collections::{HashMap, VecDeque},
+};
+
 use ratatui::{
     buffer::Buffer,
     layout::{Position, Rect},
     style::{Modifier, Style},
+    text::Span,
     widgets::{Block, Borders, Widget, WidgetRef},
 };
-use tui_textarea::{CursorMove, TextArea};
+use tui_textarea::{CursorMove, Input, Key, Scrolling, TextArea};
 
-use crate::editor::{EditorStyle, create_block};
+use crate::{
+    editor::{EditorStyle, create_block},
+    spelling::{Spellchecker, word_ranges},
+};
 
 use super::{
-    EditorAction, EditorActions, EditorMode, EditorPendingAction, TextObject, TextObjectModifier,
-    VisualMode, cursor_style,
+    BlockEdge, CharSearch, EditorAction, EditorActions, EditorMode, EditorPendingAction,
+    FieldPicker, MacroRecorder, TextObject, TextObjectModifier, VisualMode, cursor_style,
 };
 
+#[derive(Debug, Clone, Copy)]
+struct BlockInsert {
+    top: usize,
+    bottom: usize,
+    col: usize,
+    edge: BlockEdge,
+    line_len: usize,
+}
+
+const YANK_RING_SIZE: usize = 9;
+const DEFAULT_TEXT_WIDTH: usize = 80;
+const JUMP_LIST_SIZE: usize = 100;
+const CURSOR_HISTORY_SIZE: usize = 100;
+// One action can push many tui-textarea history entries, so keep enough of them for every
+// action in the cursor history
+const TEXTAREA_HISTORY_SIZE: usize = CURSOR_HISTORY_SIZE * 50;
+
+// The text around one action that changed it, and where the cursor was before. Undo and redo
+// step tui-textarea's history until the text matches, however many entries the action pushed
+#[derive(Debug, Clone)]
+struct UndoEntry {
+    cursor: (usize, usize),
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Register {
+    text: String,
+    yank_type: Option<TextObject>,
+}
+
 #[allow(dead_code)]
 pub struct EditorState {
     mode: EditorMode,
     pending_action: Option<EditorPendingAction>,
     yank_type: Option<TextObject>,
+    block_anchor: Option<(usize, usize)>,
+    block_insert: Option<BlockInsert>,
+    last_find: Option<(CharSearch, char)>,
+    recording_change: Option<Vec<EditorAction>>,
+    last_change: Option<Vec<EditorAction>>,
+    registers: HashMap<char, Register>,
+    yank_ring: VecDeque<Register>,
+    selected_register: Option<char>,
+    search_query: Option<String>,
+    search_origin: (usize, usize),
+    macros: MacroRecorder,
+    marks: HashMap<char, (usize, usize)>,
+    jump_list: Vec<(usize, usize)>,
+    jump_index: usize,
+    cursor_history: Vec<UndoEntry>,
+    undo_count: usize,
+    // Whether the last undo step is the open insert session, which grows as the user types
+    insert_session: bool,
+    replaced: Vec<Option<char>>,
 }
 
 #[allow(dead_code)]
@@ -26,8 +86,16 @@ pub struct Editor {
     state: EditorState,
     textarea: TextArea<'static>,
     single_line: bool,
-    validator: Option<Box<dyn Fn(&TextArea) -> bool>>,
+    validator: Option<Box<dyn Fn(&TextArea) -> Result<(), String>>>,
+    editor_style: EditorStyle,
+    picker: Option<FieldPicker>,
     current_block: Option<Block<'static>>,
+    viewport: Cell<(u16, u16)>,
+    text_width: usize,
+    wrap: bool,
+    // Top visual row and width of the last soft wrapped render
+    wrap_view: Cell<(usize, u16)>,
+    spellchecker: Option<Spellchecker>,
 }
 
 impl Default for Editor {
@@ -36,8 +104,27 @@ impl Default for Editor {
             mode: EditorMode::Normal,
             pending_action: None,
             yank_type: None,
+            block_anchor: None,
+            block_insert: None,
+            last_find: None,
+            recording_change: None,
+            last_change: None,
+            registers: HashMap::new(),
+            yank_ring: VecDeque::new(),
+            selected_register: None,
+            search_query: None,
+            search_origin: (0, 0),
+            macros: MacroRecorder::default(),
+            marks: HashMap::new(),
+            jump_list: Vec::new(),
+            jump_index: 0,
+            cursor_history: Vec::new(),
+            undo_count: 0,
+            insert_session: false,
+            replaced: Vec::new(),
         };
         let mut textarea = TextArea::default();
+        textarea.set_max_histories(TEXTAREA_HISTORY_SIZE);
         textarea.set_selection_style(Style::default().add_modifier(Modifier::REVERSED));
         // textarea.set_cursor_line_style(Style::default().bg(Color::Rgb(50, 50, 50)));
         textarea.set_cursor_line_style(Style::default());
@@ -47,7 +134,14 @@ impl Default for Editor {
             textarea,
             single_line: false,
             validator: None,
+            editor_style: EditorStyle::Active,
+            picker: None,
             current_block: None,
+            viewport: Cell::new((0, 0)),
+            text_width: DEFAULT_TEXT_WIDTH,
+            wrap: false,
+            wrap_view: Cell::new((0, 0)),
+            spellchecker: None,
         }
     }
 }
@@ -66,12 +160,32 @@ impl Editor {
 
     pub fn with_validator<F>(mut self, validator: F) -> Self
     where
-        F: 'static + Fn(&TextArea) -> bool,
+        F: 'static + Fn(&TextArea) -> Result<(), String>,
     {
         self.validator = Some(Box::new(validator));
         self
     }
 
+    pub fn with_picker(mut self, picker: FieldPicker) -> Self {
+        self.picker = Some(picker);
+        self
+    }
+
+    pub fn with_text_width(mut self, text_width: usize) -> Self {
+        self.text_width = text_width;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_spellchecker(mut self, spellchecker: Option<Spellchecker>) -> Self {
+        self.spellchecker = spellchecker;
+        self
+    }
+
     pub fn with_content(mut self, content: &str) -> Self {
         self.textarea.insert_str(content);
         self
@@ -129,11 +243,140 @@ impl Editor {
         self.textarea.set_desired_column(col);
     }
 
+    // Moves to `row` as close to `desired_col` as the line allows, remembering `desired_col`
+    // so that moving on through shorter lines or fields doesn't lose it
+    pub fn jump_to_row(&mut self, row: usize, desired_col: usize) {
+        let row = row.min(self.textarea.lines().len().saturating_sub(1));
+        let len = char_len(&self.textarea.lines()[row]);
+        // Normal and visual mode rest on the last character, insert mode can sit after it
+        let max_col = match self.state.mode {
+            EditorMode::Insert | EditorMode::Replace => len,
+            EditorMode::Normal | EditorMode::Visual(_) => len.saturating_sub(1),
+        };
+        let col = desired_col.min(max_col);
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+        self.textarea.set_desired_column(desired_col);
+    }
+
+    // Normal mode rests on the last character like vim, never after it. The desired column
+    // is kept so that `j`/`k` past a short line still return to the longer column
+    fn clamp_cursor(&mut self) {
+        let (row, col) = self.textarea.cursor();
+        let Some(line) = self.textarea.lines().get(row) else {
+            return;
+        };
+        let max_col = char_len(line).saturating_sub(1);
+        if col > max_col {
+            let desired_col = self.textarea.get_desired_column();
+            self.textarea
+                .move_cursor(CursorMove::Jump(row as u16, max_col as u16));
+            self.textarea.set_desired_column(desired_col);
+        }
+    }
+
     pub fn set_cursor_pos(mut self, row: u16, col: u16) -> Self {
         self.textarea.move_cursor(CursorMove::Jump(row, col));
         self
     }
 
+    // Replaces the whole text as a single undo step, leaving the yank register alone. Single
+    // line fields get their lines joined with spaces
+    pub fn set_text(&mut self, text: &str) {
+        let text = if self.single_line {
+            text.lines().collect::<Vec<_>>().join(" ")
+        } else {
+            text.to_string()
+        };
+        let cursor = self.textarea.cursor();
+        let before = self.textarea.lines().to_vec();
+        let yank = self.textarea.yank_text();
+        self.textarea.select_all();
+        self.textarea.cut();
+        self.textarea.insert_str(&text);
+        self.textarea.set_yank_text(yank);
+        if self.state.mode == EditorMode::Normal {
+            self.clamp_cursor();
+        }
+        self.add_to_cursor_history(cursor, before);
+        self.set_editor_style(self.editor_style);
+    }
+
+    // The misspelled word the cursor is on or just after, as its row, char range and text
+    fn misspelled_word_at_cursor(&self) -> Option<(usize, (usize, usize), String)> {
+        let spellchecker = self.spellchecker.as_ref()?;
+        let (row, col) = self.textarea.cursor();
+        let chars: Vec<char> = self.textarea.lines()[row].chars().collect();
+        let (start, end) = word_ranges(&chars)
+            .into_iter()
+            .find(|&(start, end)| start <= col && col <= end)?;
+        let word: String = chars[start..end].iter().collect();
+        spellchecker
+            .is_misspelled(&word)
+            .then_some((row, (start, end), word))
+    }
+
+    pub fn spelling_suggestions(&self) -> Option<(String, Vec<String>)> {
+        let (_, _, word) = self.misspelled_word_at_cursor()?;
+        let suggestions = self.spellchecker.as_ref()?.suggestions(&word);
+        Some((word, suggestions))
+    }
+
+    // Swaps the misspelled word under the cursor for the replacement as a single undo step
+    pub fn replace_misspelled_word(&mut self, replacement: &str) {
+        let Some((row, (start, end), _)) = self.misspelled_word_at_cursor() else {
+            return;
+        };
+        let cursor = self.textarea.cursor();
+        let before = self.textarea.lines().to_vec();
+        let yank = self.textarea.yank_text();
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, start as u16));
+        self.textarea.start_selection();
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, end as u16));
+        self.textarea.cut();
+        self.textarea.insert_str(replacement);
+        self.textarea.set_yank_text(yank);
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, start as u16));
+        self.add_to_cursor_history(cursor, before);
+    }
+
+    fn render_spelling(&self, area: Rect, buf: &mut Buffer) {
+        let Some(spellchecker) = &self.spellchecker else {
+            return;
+        };
+        let inner = match &self.current_block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        let tab_length = self.textarea.tab_length() as usize;
+        let (top_row, top_col) = self.viewport.get();
+        let style = Style::default().add_modifier(Modifier::UNDERLINED);
+        let lines = self.textarea.lines().iter().enumerate();
+        for (row, line) in lines.skip(top_row as usize).take(inner.height as usize) {
+            let y = inner.y + (row - top_row as usize) as u16;
+            for (start, end) in spellchecker.misspelled(line) {
+                let left = cell_width(line.chars().take(start), tab_length);
+                let right = cell_width(line.chars().take(end), tab_length);
+                for cell in left.max(top_col as usize)..right {
+                    let x = inner.x as usize + cell - top_col as usize;
+                    if x >= inner.right() as usize {
+                        break;
+                    }
+                    if let Some(cell) = buf.cell_mut(Position::new(x as u16, y)) {
+                        cell.set_style(style);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_picker(&self) -> Option<FieldPicker> {
+        self.picker
+    }
+
     pub fn get_title(&self) -> Option<String> {
         self.title.clone()
     }
@@ -160,15 +403,194 @@ impl Editor {
         } else {
             local_pos.into()
         };
-        self.textarea.move_cursor(CursorMove::Jump(y, x));
+        if self.wrap {
+            let (row, col) = self.wrapped_position(x, y);
+            self.textarea
+                .move_cursor(CursorMove::Jump(row as u16, col as u16));
+        } else {
+            let (top_row, top_col) = self.viewport.get();
+            self.textarea
+                .move_cursor(CursorMove::Jump(y + top_row, x + top_col));
+        }
+        if self.state.mode == EditorMode::Normal {
+            self.clamp_cursor();
+        }
+    }
+
+    pub fn on_scroll(&mut self, delta: i16) {
+        let mvmt = if delta < 0 {
+            CursorMove::Up
+        } else {
+            CursorMove::Down
+        };
+        for _ in 0..delta.unsigned_abs() {
+            self.textarea.move_cursor(mvmt);
+        }
+    }
+
+    // Scrolls the view sideways, pulling the cursor along when it would leave the view. A line
+    // shorter than the new left edge scrolls back to its end on the next render
+    fn scroll_columns(&mut self, cols: i16) {
+        if self.wrap {
+            return;
+        }
+        self.textarea.scroll(Scrolling::Delta { rows: 0, cols });
+        let (top_row, top_col) = self.viewport.get();
+        self.viewport
+            .set((top_row, top_col.saturating_add_signed(cols)));
+    }
+
+    // Wrapped rendering needs tui-textarea scrolled to the top left, so switching wraps scrolls
+    // it back there, keeping the cursor where it was
+    fn toggle_wrap(&mut self) {
+        self.wrap = !self.wrap;
+        let (row, col) = self.textarea.cursor();
+        let desired_col = self.textarea.get_desired_column();
+        let (top_row, top_col) = self.viewport.get();
+        self.textarea.scroll(Scrolling::Delta {
+            rows: -(top_row as i16),
+            cols: -(top_col as i16),
+        });
+        self.viewport.set((0, 0));
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+        self.textarea.set_desired_column(desired_col);
+    }
+
+    // Maps a position in the wrapped view back to a row and a char column
+    fn wrapped_position(&self, x: u16, y: u16) -> (usize, usize) {
+        let tab_length = self.textarea.tab_length() as usize;
+        let (top, width) = self.wrap_view.get();
+        let width = width.max(1) as usize;
+        let lines = self.textarea.lines();
+        let mut visual = top + y as usize;
+        for (row, line) in lines.iter().enumerate() {
+            let rows = cell_width(line.chars(), tab_length).div_ceil(width).max(1);
+            if visual < rows {
+                let cell = visual * width + x as usize;
+                let col = (1..=char_len(line))
+                    .find(|&end| cell_width(line.chars().take(end), tab_length) > cell)
+                    .map_or(char_len(line), |end| end - 1);
+                return (row, col);
+            }
+            visual -= rows;
+        }
+        let last = lines.len().saturating_sub(1);
+        (last, char_len(&lines[last]))
+    }
+
+    // tui-textarea can't wrap, so this renders it unwrapped into a scratch buffer wide enough
+    // for every line, then copies each line into the pane in slices of the pane's width
+    fn render_wrapped(&self, area: Rect, buf: &mut Buffer) {
+        let inner = match &self.current_block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        if inner.is_empty() || self.textarea.is_empty() {
+            self.update_viewport(area);
+            (&self.textarea).render(area, buf);
+            self.render_block_selection(area, buf);
+            return;
+        }
+        let tab_length = self.textarea.tab_length() as usize;
+        let lines = self.textarea.lines();
+        let (cursor_row, cursor_col) = self.textarea.cursor();
+        let cursor_x = cell_width(lines[cursor_row].chars().take(cursor_col), tab_length);
+        // The cursor can sit one cell past the end of its line
+        let widths: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .map(|(row, line)| {
+                let width = cell_width(line.chars(), tab_length);
+                if row == cursor_row {
+                    width.max(cursor_x + 1)
+                } else {
+                    width
+                }
+            })
+            .collect();
+        let width = inner.width as usize;
+        let rows = |cells: usize| cells.div_ceil(width).max(1);
+        let cursor_visual = widths[..cursor_row]
+            .iter()
+            .map(|&cells| rows(cells))
+            .sum::<usize>()
+            + cursor_x / width;
+        let (top, _) = self.wrap_view.get();
+        let top = next_scroll_top(top as u16, cursor_visual as u16, inner.height) as usize;
+        self.wrap_view.set((top, inner.width));
+
+        let margin = (area.width - inner.width, area.height - inner.height);
+        let max_width = widths.iter().copied().max().unwrap_or(0);
+        let scratch_area = Rect::new(
+            0,
+            0,
+            (max_width as u16).saturating_add(margin.0),
+            (lines.len() as u16).saturating_add(margin.1),
+        );
+        let mut scratch = Buffer::empty(scratch_area);
+        self.update_viewport(scratch_area);
+        (&self.textarea).render(scratch_area, &mut scratch);
+        self.render_block_selection(scratch_area, &mut scratch);
+        self.render_spelling(scratch_area, &mut scratch);
+        let origin = match &self.current_block {
+            Some(block) => {
+                block.render_ref(area, buf);
+                block.inner(scratch_area).as_position()
+            }
+            None => Position::ORIGIN,
+        };
+
+        let mut visual = 0;
+        for (row, &cells) in widths.iter().enumerate() {
+            for slice in 0..rows(cells) {
+                if visual >= top + inner.height as usize {
+                    return;
+                }
+                if visual >= top {
+                    let y = inner.y + (visual - top) as u16;
+                    for x in 0..inner.width {
+                        let source = Position::new(
+                            origin.x + (slice * width) as u16 + x,
+                            origin.y + row as u16,
+                        );
+                        if let (Some(cell), Some(target)) =
+                            (scratch.cell(source), buf.cell_mut((inner.x + x, y)))
+                        {
+                            *target = cell.clone();
+                        }
+                    }
+                }
+                visual += 1;
+            }
+        }
+    }
+
+    // Mirrors tui-textarea's scrolling so mouse positions can be mapped back to text positions.
+    // tui-textarea doesn't expose its scroll position, so this runs the same update from the
+    // same starting point just before each render
+    fn update_viewport(&self, area: Rect) {
+        let inner = match &self.current_block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        let (row, col) = self.textarea.cursor();
+        let (top_row, top_col) = self.viewport.get();
+        self.viewport.set((
+            next_scroll_top(top_row, row as u16, inner.height),
+            next_scroll_top(top_col, col as u16, inner.width),
+        ));
     }
 
+    // Normal and visual mode never go past the last character, so that counts as the end
     pub fn is_cursor_at_line_end(&self) -> bool {
         let (row, col) = self.textarea.cursor();
-        if let Some(line) = self.textarea.lines().get(row) {
-            col >= line.len()
-        } else {
-            false
+        let Some(line) = self.textarea.lines().get(row) else {
+            return false;
+        };
+        match self.state.mode {
+            EditorMode::Insert | EditorMode::Replace => col >= char_len(line),
+            EditorMode::Normal | EditorMode::Visual(_) => col >= char_len(line).saturating_sub(1),
         }
     }
 
@@ -177,21 +599,45 @@ impl Editor {
         col == 0
     }
 
+    // None when there is no validator or the field is empty
+    pub fn validate(&self) -> Option<Result<(), String>> {
+        let validator = self.validator.as_ref()?;
+        if self
+            .textarea
+            .lines()
+            .iter()
+            .all(|line| line.trim().is_empty())
+        {
+            return None;
+        }
+        Some(validator(&self.textarea))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !matches!(self.validate(), Some(Err(_)))
+    }
+
+    pub fn validation_error(&self) -> Option<String> {
+        self.validate()?.err()
+    }
+
     pub fn set_editor_style(&mut self, style: EditorStyle) {
+        self.editor_style = style;
+        let is_valid = self.validate().map(|result| result.is_ok());
         match style {
             EditorStyle::Active => {
                 let is_active = true;
                 self.set_cursor_style(cursor_style(self.get_mode(), is_active));
                 self.set_style(Style::default());
                 let borders = Borders::ALL;
-                self.set_block(create_block(self.get_title(), is_active, borders))
+                self.set_block(create_block(self.get_title(), is_active, borders, is_valid))
             }
             EditorStyle::Inactive => {
                 let is_active = false;
                 self.set_cursor_style(cursor_style(self.get_mode(), is_active));
                 self.set_style(Style::default().add_modifier(Modifier::DIM));
                 let borders = Borders::ALL;
-                self.set_block(create_block(self.get_title(), is_active, borders))
+                self.set_block(create_block(self.get_title(), is_active, borders, is_valid))
             }
         }
     }
@@ -243,23 +689,987 @@ fn select_current_paragraph(
     (current_row, current_col)
 }
 
-impl EditorActions for Editor {
+fn char_len(line: &str) -> usize {
+    line.chars().count()
+}
+
+// Terminal cells taken by the chars, with tabs reaching the next tab stop as tui-textarea draws
+// them
+fn cell_width(chars: impl Iterator<Item = char>, tab_length: usize) -> usize {
+    let mut width = 0;
+    let mut encoded = [0; 4];
+    for c in chars {
+        width += match c {
+            '\t' if tab_length > 0 => tab_length - width % tab_length,
+            '\t' => 0,
+            c => Span::raw(&*c.encode_utf8(&mut encoded)).width(),
+        };
+    }
+    width
+}
+
+// Finds the target column of an f/t/F/T search on the current line
+fn find_char(line: &str, col: usize, search: CharSearch, c: char, repeat: bool) -> Option<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    // Repeating a till search would otherwise stop right before the same character again
+    let skip = if repeat { 1 } else { 0 };
+    match search {
+        CharSearch::Find => (col + 1..chars.len()).find(|&i| chars[i] == c),
+        CharSearch::Till => (col + 1 + skip..chars.len())
+            .find(|&i| chars[i] == c)
+            .map(|i| i - 1),
+        CharSearch::FindBack => (0..col).rev().find(|&i| chars[i] == c),
+        CharSearch::TillBack => (0..col.saturating_sub(skip))
+            .rev()
+            .find(|&i| chars[i] == c)
+            .map(|i| i + 1),
+    }
+}
+
+impl Editor {
+    // Returns the (top, left) and (bottom, right) corners of the visual block
+    fn block_bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        if self.state.mode != EditorMode::Visual(VisualMode::Block) {
+            return None;
+        }
+        let (anchor_row, anchor_col) = self.state.block_anchor?;
+        let (row, col) = self.textarea.cursor();
+        Some((
+            (anchor_row.min(row), anchor_col.min(col)),
+            (anchor_row.max(row), anchor_col.max(col)),
+        ))
+    }
+
+    fn block_text(&self) -> Option<String> {
+        let ((top, left), (bottom, right)) = self.block_bounds()?;
+        let lines = self.textarea.lines();
+        let text = (top..=bottom)
+            .map(|row| {
+                lines[row]
+                    .chars()
+                    .skip(left)
+                    .take(right + 1 - left)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(text)
+    }
+
+    fn move_to_char(&mut self, search: CharSearch, c: char, repeat: bool) {
+        let (row, col) = self.textarea.cursor();
+        if let Some(target) = find_char(&self.textarea.lines()[row], col, search, c, repeat) {
+            self.textarea
+                .move_cursor(CursorMove::Jump(row as u16, target as u16));
+        }
+    }
+
+    // Selects from the cursor to the target of a character search, returning the start column
+    fn select_to_char(&mut self, search: CharSearch, c: char) -> Option<usize> {
+        let (row, col) = self.textarea.cursor();
+        let target = find_char(&self.textarea.lines()[row], col, search, c, false)?;
+        let (start, end) = match search {
+            CharSearch::Find | CharSearch::Till => (col, target + 1),
+            CharSearch::FindBack | CharSearch::TillBack => (target, col),
+        };
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, start as u16));
+        self.textarea.start_selection();
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, end as u16));
+        Some(start)
+    }
+
+    fn delete_block(&mut self) {
+        if let Some(((top, left), (bottom, right))) = self.block_bounds() {
+            let yanked = self.block_text().unwrap_or_default();
+            for row in top..=bottom {
+                let len = char_len(&self.textarea.lines()[row]);
+                if left < len {
+                    self.textarea
+                        .move_cursor(CursorMove::Jump(row as u16, left as u16));
+                    self.textarea.delete_str((right + 1).min(len) - left);
+                }
+            }
+            self.textarea.set_yank_text(yanked);
+            self.textarea
+                .move_cursor(CursorMove::Jump(top as u16, left as u16));
+            // Collapse the block to its left edge so a following block insert spans the same rows
+            self.state.block_anchor = Some((bottom, left));
+        }
+    }
+
+    fn start_block_insert(&mut self, edge: BlockEdge) {
+        if let Some(((top, left), (bottom, right))) = self.block_bounds() {
+            let col = match edge {
+                BlockEdge::Start => left,
+                BlockEdge::End => (right + 1).min(char_len(&self.textarea.lines()[top])),
+            };
+            self.textarea
+                .move_cursor(CursorMove::Jump(top as u16, col as u16));
+            self.state.block_insert = Some(BlockInsert {
+                top,
+                bottom,
+                col,
+                edge,
+                line_len: char_len(&self.textarea.lines()[top]),
+            });
+            self.state.block_anchor = None;
+            self.state.mode = EditorMode::Insert;
+            self.textarea
+                .set_cursor_style(cursor_style(self.state.mode, true));
+        }
+    }
+
+    // Replicates the text typed on the first row of a block insert onto the remaining rows
+    fn finish_block_insert(&mut self) {
+        let Some(insert) = self.state.block_insert.take() else {
+            return;
+        };
+        let lines = self.textarea.lines();
+        if insert.bottom >= lines.len() {
+            return;
+        }
+        let line_len = char_len(&lines[insert.top]);
+        if line_len <= insert.line_len {
+            return;
+        }
+        let inserted: String = lines[insert.top]
+            .chars()
+            .skip(insert.col)
+            .take(line_len - insert.line_len)
+            .collect();
+        for row in insert.top + 1..=insert.bottom {
+            let len = char_len(&self.textarea.lines()[row]);
+            if len < insert.col {
+                match insert.edge {
+                    BlockEdge::Start => continue,
+                    BlockEdge::End => {
+                        self.textarea
+                            .move_cursor(CursorMove::Jump(row as u16, len as u16));
+                        self.textarea.insert_str(" ".repeat(insert.col - len));
+                    }
+                }
+            }
+            self.textarea
+                .move_cursor(CursorMove::Jump(row as u16, insert.col as u16));
+            self.textarea.insert_str(&inserted);
+        }
+        self.textarea
+            .move_cursor(CursorMove::Jump(insert.top as u16, insert.col as u16));
+    }
+
+    fn paste(&mut self) {
+        if !self.load_register() {
+            return;
+        }
+        match self.state.yank_type {
+            Some(TextObject::Line) => {
+                if self.single_line {
+                    return;
+                }
+                let yanked = &self.textarea.yank_text();
+                self.textarea.set_yank_text(yanked.trim_end());
+                self.textarea.move_cursor(CursorMove::End);
+                let _ = self.textarea.insert_newline();
+                self.textarea.paste();
+                self.textarea.move_cursor(CursorMove::Head);
+            }
+            Some(TextObject::Block) => {
+                self.paste_block();
+            }
+            _ => {
+                self.textarea.move_cursor(CursorMove::Forward);
+                self.textarea.paste();
+            }
+        }
+    }
+
+    fn paste_block(&mut self) {
+        let yanked = self.textarea.yank_text();
+        let (row, col) = self.textarea.cursor();
+        let col = (col + 1).min(char_len(&self.textarea.lines()[row]));
+        for (i, text) in yanked.lines().enumerate() {
+            let target = row + i;
+            if target >= self.textarea.lines().len() {
+                if self.single_line {
+                    break;
+                }
+                self.textarea.move_cursor(CursorMove::Bottom);
+                self.textarea.move_cursor(CursorMove::End);
+                self.textarea.insert_newline();
+            }
+            let len = char_len(&self.textarea.lines()[target]);
+            if len < col {
+                self.textarea
+                    .move_cursor(CursorMove::Jump(target as u16, len as u16));
+                self.textarea.insert_str(" ".repeat(col - len));
+            }
+            self.textarea
+                .move_cursor(CursorMove::Jump(target as u16, col as u16));
+            self.textarea.insert_str(text);
+        }
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+    }
+
+    fn render_block_selection(&self, area: Rect, buf: &mut Buffer) {
+        let Some(((top, left), (bottom, right))) = self.block_bounds() else {
+            return;
+        };
+        let inner = match &self.current_block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        let (top_row, top_col) = self.viewport.get();
+        let (top_row, top_col) = (top_row as usize, top_col as usize);
+        let style = Style::default().add_modifier(Modifier::REVERSED);
+        for row in top.max(top_row)..=bottom.min(top_row + inner.height as usize) {
+            let len = char_len(&self.textarea.lines()[row]);
+            let y = inner.y + (row - top_row) as u16;
+            if y >= inner.bottom() {
+                break;
+            }
+            for col in left.max(top_col)..=right.min(len.saturating_sub(1)) {
+                let x = inner.x + (col - top_col) as u16;
+                if x >= inner.right() {
+                    break;
+                }
+                if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                    cell.set_style(style);
+                }
+            }
+        }
+    }
+}
+
+fn find_quote_bounds(line: &[char], col: usize, quote: char) -> Option<(usize, usize)> {
+    let quotes: Vec<usize> = (0..line.len()).filter(|&i| line[i] == quote).collect();
+    // Prefer the pair around the cursor, otherwise fall back to the next pair on the line
+    quotes
+        .chunks_exact(2)
+        .find(|pair| pair[0] <= col && col <= pair[1])
+        .or_else(|| quotes.chunks_exact(2).find(|pair| pair[0] > col))
+        .map(|pair| (pair[0], pair[1]))
+}
+
+fn prev_pos(lines: &[Vec<char>], (row, col): (usize, usize)) -> Option<(usize, usize)> {
+    if col > 0 {
+        Some((row, col - 1))
+    } else if row > 0 {
+        Some((row - 1, lines[row - 1].len()))
+    } else {
+        None
+    }
+}
+
+fn next_pos(lines: &[Vec<char>], (row, col): (usize, usize)) -> Option<(usize, usize)> {
+    if col < lines[row].len() {
+        Some((row, col + 1))
+    } else if row + 1 < lines.len() {
+        Some((row + 1, 0))
+    } else {
+        None
+    }
+}
+
+fn find_bracket_bounds(
+    lines: &[Vec<char>],
+    cursor: (usize, usize),
+    open: char,
+    close: char,
+) -> Option<((usize, usize), (usize, usize))> {
+    let char_at = |(row, col): (usize, usize)| lines[row].get(col).copied();
+    let mut depth = 0;
+    let mut pos = cursor;
+    let start = loop {
+        match char_at(pos) {
+            Some(c) if c == close && pos != cursor => depth += 1,
+            Some(c) if c == open => {
+                if depth == 0 {
+                    break pos;
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+        pos = prev_pos(lines, pos)?;
+    };
+    depth = 0;
+    pos = start;
+    let end = loop {
+        pos = next_pos(lines, pos)?;
+        match char_at(pos) {
+            Some(c) if c == open => depth += 1,
+            Some(c) if c == close => {
+                if depth == 0 {
+                    break pos;
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    };
+    Some((start, end))
+}
+
+// Selects the quote or bracket pair around the cursor, returning the start of the selection
+fn select_current_pair(
+    textarea: &mut TextArea,
+    open: char,
+    close: char,
+    modifier: TextObjectModifier,
+) -> Option<(usize, usize)> {
+    let lines: Vec<Vec<char>> = textarea
+        .lines()
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect();
+    let (row, col) = textarea.cursor();
+    let (start, end) = if open == close {
+        let (start, end) = find_quote_bounds(&lines[row], col, open)?;
+        ((row, start), (row, end))
+    } else {
+        find_bracket_bounds(&lines, (row, col), open, close)?
+    };
+    let (start, end) = match modifier {
+        TextObjectModifier::Inner => ((start.0, start.1 + 1), end),
+        TextObjectModifier::Around => (start, (end.0, end.1 + 1)),
+    };
+    textarea.move_cursor(CursorMove::Jump(start.0 as u16, start.1 as u16));
+    textarea.start_selection();
+    textarea.move_cursor(CursorMove::Jump(end.0 as u16, end.1 as u16));
+    Some(start)
+}
+
+// Splits a markdown list marker ("- ", "* ", "+ ", "1. ", "1) ") off the start of a trimmed line
+fn split_list_marker(text: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = text
+        .strip_prefix("- ")
+        .or_else(|| text.strip_prefix("* "))
+        .or_else(|| text.strip_prefix("+ "))
+    {
+        return Some((&text[..2], rest));
+    }
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && (text[digits..].starts_with(". ") || text[digits..].starts_with(") ")) {
+        return Some((&text[..digits + 2], &text[digits + 2..]));
+    }
+    None
+}
+
+// Re-wraps lines to the given width, keeping indentation and starting a new item at each list
+// marker. Blank lines separate paragraphs and are kept as they are
+fn reflow_lines(lines: &[String], width: usize) -> Vec<String> {
+    let mut items: Vec<Option<(String, String, Vec<&str>)>> = Vec::new();
+    for line in lines {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        match (split_list_marker(trimmed), items.last_mut()) {
+            _ if trimmed.is_empty() => items.push(None),
+            (Some((marker, rest)), _) => {
+                items.push(Some((
+                    indent.to_string(),
+                    marker.to_string(),
+                    rest.split_whitespace().collect(),
+                )));
+            }
+            (None, Some(Some((_, _, words)))) => words.extend(trimmed.split_whitespace()),
+            (None, Some(None) | None) => {
+                items.push(Some((
+                    indent.to_string(),
+                    String::new(),
+                    trimmed.split_whitespace().collect(),
+                )));
+            }
+        }
+    }
+    let mut reflowed = Vec::new();
+    for item in items {
+        let Some((indent, marker, words)) = item else {
+            reflowed.push(String::new());
+            continue;
+        };
+        let first_prefix = format!("{}{}", indent, marker);
+        let prefix = format!("{}{}", indent, " ".repeat(marker.chars().count()));
+        let mut current = first_prefix.clone();
+        let mut current_len = char_len(&current);
+        let mut empty = true;
+        for word in words {
+            let word_len = char_len(word);
+            if !empty && current_len + 1 + word_len > width {
+                reflowed.push(current);
+                current = prefix.clone();
+                current_len = char_len(&current);
+                empty = true;
+            }
+            if !empty {
+                current.push(' ');
+                current_len += 1;
+            }
+            current.push_str(word);
+            current_len += word_len;
+            empty = false;
+        }
+        reflowed.push(current);
+    }
+    reflowed
+}
+
+// Whether an action modifies the text and should be repeatable with `.`
+fn is_change(action: &EditorAction) -> bool {
+    match action {
+        EditorAction::Delete(_)
+        | EditorAction::ReplaceChar(_)
+        | EditorAction::Overwrite(_)
+        | EditorAction::RestoreReplaced
+        | EditorAction::Paste
+        | EditorAction::Insert(_)
+        | EditorAction::Reflow(_)
+        | EditorAction::JoinLines(_)
+        | EditorAction::Indent(_)
+        | EditorAction::Dedent(_)
+        | EditorAction::SetMode(EditorMode::Insert | EditorMode::Replace) => true,
+        EditorAction::MultiAction(actions) => actions.iter().any(is_change),
+        _ => false,
+    }
+}
+
+// Whether an action can start an insert session, so the text before it is kept for undo
+fn enters_insert(action: &EditorAction) -> bool {
+    match action {
+        EditorAction::SetMode(EditorMode::Insert | EditorMode::Replace)
+        | EditorAction::InsertBlock(_) => true,
+        EditorAction::MultiAction(actions) => actions.iter().any(enters_insert),
+        _ => false,
+    }
+}
+
+fn is_typing(mode: EditorMode) -> bool {
+    matches!(mode, EditorMode::Insert | EditorMode::Replace)
+}
+
+// Whether an action can edit the text, so the text is kept before it runs in case it changes
+fn is_edit(action: &EditorAction) -> bool {
+    match action {
+        EditorAction::SetMode(_) => false,
+        EditorAction::ApplyInput(_) | EditorAction::RepeatChange => true,
+        EditorAction::MultiAction(actions) => actions.iter().any(is_edit),
+        action => is_change(action),
+    }
+}
+
+impl Editor {
+    // Records an undo step if the text no longer matches `before`
+    fn add_to_cursor_history(&mut self, cursor: (usize, usize), before: Vec<String>) {
+        if before.as_slice() == self.textarea.lines() {
+            return;
+        }
+        self.push_undo_entry(cursor, before);
+    }
+
+    fn push_undo_entry(&mut self, cursor: (usize, usize), before: Vec<String>) {
+        // New changes drop the undone steps, as tui-textarea drops its redo entries
+        let kept = self.state.cursor_history.len() - self.state.undo_count;
+        self.state.cursor_history.truncate(kept);
+        self.state.undo_count = 0;
+        self.state.insert_session = false;
+        self.state.cursor_history.push(UndoEntry {
+            cursor,
+            before,
+            after: self.textarea.lines().to_vec(),
+        });
+        if self.state.cursor_history.len() > CURSOR_HISTORY_SIZE {
+            self.state.cursor_history.remove(0);
+        }
+    }
+
+    // Everything typed between entering and leaving insert mode is undone as one step, like vim.
+    // The step is pushed on entering, so it holds the change that entered insert mode too
+    fn start_insert_session(&mut self, cursor: (usize, usize), before: Vec<String>) {
+        self.push_undo_entry(cursor, before);
+        self.state.insert_session = true;
+    }
+
+    fn update_insert_session(&mut self, cursor: (usize, usize), before: Vec<String>) {
+        if !self.state.insert_session || self.state.undo_count > 0 {
+            // An undo or another step got in between, so the session starts over from here
+            if before.as_slice() != self.textarea.lines() {
+                self.start_insert_session(cursor, before);
+            }
+            return;
+        }
+        if let Some(entry) = self.state.cursor_history.last_mut() {
+            entry.after = self.textarea.lines().to_vec();
+        }
+    }
+
+    fn end_insert_session(&mut self, before: Option<((usize, usize), Vec<String>)>) {
+        if !self.state.insert_session || self.state.undo_count > 0 {
+            self.state.insert_session = false;
+            if let Some((cursor, before)) = before {
+                self.add_to_cursor_history(cursor, before);
+            }
+            return;
+        }
+        self.state.insert_session = false;
+        let lines = self.textarea.lines().to_vec();
+        if let Some(entry) = self.state.cursor_history.last_mut() {
+            entry.after = lines;
+            // Nothing was typed, so there is nothing to undo
+            if entry.before == entry.after {
+                self.state.cursor_history.pop();
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        let Some(index) = self
+            .state
+            .cursor_history
+            .len()
+            .checked_sub(self.state.undo_count + 1)
+        else {
+            return;
+        };
+        let entry = &self.state.cursor_history[index];
+        while self.textarea.lines() != entry.before.as_slice() {
+            if !self.textarea.undo() {
+                // tui-textarea no longer has the entries this step needs
+                self.state.cursor_history.clear();
+                self.state.undo_count = 0;
+                return;
+            }
+        }
+        let cursor = entry.cursor;
+        self.state.undo_count += 1;
+        self.restore_cursor(cursor);
+    }
+
+    fn redo(&mut self) {
+        if self.state.undo_count == 0 {
+            return;
+        }
+        let index = self.state.cursor_history.len() - self.state.undo_count;
+        let entry = &self.state.cursor_history[index];
+        while self.textarea.lines() != entry.after.as_slice() {
+            if !self.textarea.redo() {
+                return;
+            }
+        }
+        let cursor = entry.cursor;
+        self.state.undo_count -= 1;
+        self.restore_cursor(cursor);
+    }
+
+    // Moves to a remembered position, keeping the cursor on a character as normal mode requires
+    fn restore_cursor(&mut self, (row, col): (usize, usize)) {
+        let lines = self.textarea.lines();
+        if row < lines.len() {
+            let max_col = char_len(&lines[row]).saturating_sub(1);
+            self.textarea
+                .move_cursor(CursorMove::Jump(row as u16, col.min(max_col) as u16));
+        }
+    }
+
+    fn record_change(&mut self, action: &EditorAction) {
+        match self.state.mode {
+            EditorMode::Insert | EditorMode::Replace => {
+                if let Some(change) = self.state.recording_change.as_mut() {
+                    change.push(action.clone());
+                }
+            }
+            EditorMode::Normal if is_change(action) => {
+                self.state.recording_change = Some(vec![action.clone()]);
+            }
+            _ => {}
+        }
+    }
+
+    // Copies the text just yanked or deleted into the selected register and the yank ring
+    fn store_yank(&mut self) {
+        let register = Register {
+            text: self.textarea.yank_text(),
+            yank_type: self.state.yank_type,
+        };
+        match self.state.selected_register.take() {
+            Some(name) if name.is_ascii_uppercase() => {
+                let entry = self
+                    .state
+                    .registers
+                    .entry(name.to_ascii_lowercase())
+                    .or_insert(Register {
+                        text: String::new(),
+                        yank_type: register.yank_type,
+                    });
+                entry.text.push_str(&register.text);
+            }
+            Some(name) if name != '"' => {
+                self.state.registers.insert(name, register.clone());
+            }
+            _ => {}
+        }
+        // Single characters deleted with x stay out of the ring, like vim's small deletes
+        if !matches!(register.yank_type, Some(TextObject::Char)) {
+            self.state.yank_ring.push_front(register);
+            self.state.yank_ring.truncate(YANK_RING_SIZE);
+        }
+    }
+
+    // One line per register holding text, the unnamed and numbered ones first like vim's
+    // :registers. Newlines show as ^J
+    pub fn register_lines(&self) -> Vec<String> {
+        let mut registers = Vec::new();
+        let unnamed = self.textarea.yank_text();
+        if !unnamed.is_empty() {
+            registers.push(('"', unnamed));
+        }
+        for (register, name) in self.state.yank_ring.iter().zip('1'..='9') {
+            registers.push((name, register.text.clone()));
+        }
+        let mut named: Vec<_> = self.state.registers.iter().collect();
+        named.sort_by_key(|(name, _)| **name);
+        for (name, register) in named {
+            registers.push((*name, register.text.clone()));
+        }
+        registers
+            .into_iter()
+            .map(|(name, text)| format!("\"{}  {}", name, text.replace('\n', "^J")))
+            .collect()
+    }
+
+    // Loads the selected register into the textarea's yank buffer, returning false if it is empty
+    fn load_register(&mut self) -> bool {
+        let Some(name) = self.state.selected_register.take() else {
+            return true;
+        };
+        let register = match name {
+            '"' => self.state.yank_ring.front(),
+            '1'..='9' => self
+                .state
+                .yank_ring
+                .get(name.to_digit(10).unwrap_or(1) as usize - 1),
+            _ => self.state.registers.get(&name.to_ascii_lowercase()),
+        };
+        match register.cloned() {
+            Some(register) => {
+                self.textarea.set_yank_text(register.text);
+                self.state.yank_type = register.yank_type;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Rows covered by a line-oriented operator: the cursor line or the current selection
+    fn target_rows(&self, obj: TextObject) -> (usize, usize) {
+        let (row, _col) = self.textarea.cursor();
+        match obj {
+            TextObject::Selection => {
+                if let Some(((top, _), (bottom, _))) = self.block_bounds() {
+                    (top, bottom)
+                } else if let Some(((start, _), (end, _))) = self.textarea.selection_range() {
+                    (start, end)
+                } else {
+                    (row, row)
+                }
+            }
+            _ => (row, row),
+        }
+    }
+
+    fn join_lines(&mut self, obj: TextObject) {
+        let (start, end) = self.target_rows(obj);
+        let joins = (end - start).max(1);
+        let yanked = self.textarea.yank_text();
+        self.textarea.cancel_selection();
+        for _ in 0..joins {
+            let lines = self.textarea.lines();
+            if start + 1 >= lines.len() {
+                break;
+            }
+            let line = &lines[start];
+            let next = &lines[start + 1];
+            let next_trimmed = next.trim_start();
+            let leading = char_len(next) - char_len(next_trimmed);
+            let separator = if line.is_empty()
+                || line.ends_with(' ')
+                || next_trimmed.is_empty()
+                || next_trimmed.starts_with(')')
+            {
+                ""
+            } else {
+                " "
+            };
+            let col = char_len(line);
+            self.textarea
+                .move_cursor(CursorMove::Jump(start as u16, col as u16));
+            self.textarea.delete_str(1 + leading);
+            self.textarea.insert_str(separator);
+            self.textarea
+                .move_cursor(CursorMove::Jump(start as u16, col as u16));
+        }
+        self.textarea.set_yank_text(yanked);
+    }
+
+    fn indent_lines(&mut self, obj: TextObject) {
+        let (start, end) = self.target_rows(obj);
+        let indent = self.textarea.indent();
+        self.textarea.cancel_selection();
+        for row in start..=end {
+            if !self.textarea.lines()[row].is_empty() {
+                self.textarea.move_cursor(CursorMove::Jump(row as u16, 0));
+                self.textarea.insert_str(indent);
+            }
+        }
+        self.move_to_first_non_blank(start);
+    }
+
+    fn dedent_lines(&mut self, obj: TextObject) {
+        let (start, end) = self.target_rows(obj);
+        let tab_length = self.textarea.tab_length() as usize;
+        let yanked = self.textarea.yank_text();
+        self.textarea.cancel_selection();
+        for row in start..=end {
+            let line = &self.textarea.lines()[row];
+            let width = if line.starts_with('\t') {
+                1
+            } else {
+                line.chars()
+                    .take(tab_length)
+                    .take_while(|c| *c == ' ')
+                    .count()
+            };
+            if width > 0 {
+                self.textarea.move_cursor(CursorMove::Jump(row as u16, 0));
+                self.textarea.delete_str(width);
+            }
+        }
+        self.textarea.set_yank_text(yanked);
+        self.move_to_first_non_blank(start);
+    }
+
+    fn char_under_cursor(&self) -> Option<char> {
+        let (row, col) = self.textarea.cursor();
+        self.textarea.lines()[row].chars().nth(col)
+    }
+
+    // Overwrites the character under the cursor, extending the line at EOL
+    fn overwrite(&mut self, c: char) {
+        let original = self.char_under_cursor();
+        if original.is_some() {
+            self.textarea.delete_next_char();
+        }
+        self.textarea.insert_char(c);
+        self.state.replaced.push(original);
+    }
+
+    // Undoes the last overwrite, putting back the original character
+    fn restore_replaced(&mut self) {
+        match self.state.replaced.pop() {
+            Some(Some(original)) => {
+                self.textarea.move_cursor(CursorMove::Back);
+                self.textarea.delete_next_char();
+                self.textarea.insert_char(original);
+                self.textarea.move_cursor(CursorMove::Back);
+            }
+            Some(None) => {
+                self.textarea.delete_char();
+            }
+            None => self.textarea.move_cursor(CursorMove::Back),
+        }
+    }
+
+    fn move_to_first_non_blank(&mut self, row: usize) {
+        let line = &self.textarea.lines()[row];
+        let col = char_len(line) - char_len(line.trim_start());
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+    }
+
+    fn reflow(&mut self, obj: TextObject) {
+        let (row, _col) = self.textarea.cursor();
+        let selected = self.target_rows(obj);
+        let lines = self.textarea.lines();
+        if lines[row].trim().is_empty() && !matches!(obj, TextObject::Selection) {
+            return;
+        }
+        let (start, end) = match obj {
+            TextObject::Selection => selected,
+            TextObject::ParagraphInner | TextObject::ParagraphAround => {
+                let is_blank = |r: usize| lines[r].trim().is_empty();
+                let start = (0..row).rev().find(|&r| is_blank(r)).map_or(0, |r| r + 1);
+                let end = (row + 1..lines.len())
+                    .find(|&r| is_blank(r))
+                    .map_or(lines.len() - 1, |r| r - 1);
+                (start, end)
+            }
+            _ => (row, row),
+        };
+        let reflowed = reflow_lines(&lines[start..=end], self.text_width).join("\n");
+        let end_len = char_len(&lines[end]);
+        // Replacing the range goes through the yank buffer, so keep the user's yank intact
+        let yanked = self.textarea.yank_text();
+        self.textarea.move_cursor(CursorMove::Jump(start as u16, 0));
+        self.textarea.start_selection();
+        self.textarea
+            .move_cursor(CursorMove::Jump(end as u16, end_len as u16));
+        self.textarea.cut();
+        self.textarea.insert_str(reflowed);
+        self.textarea.set_yank_text(yanked);
+        self.textarea.move_cursor(CursorMove::Jump(start as u16, 0));
+    }
+
+    fn push_jump(&mut self, pos: (usize, usize)) {
+        self.state.jump_list.truncate(self.state.jump_index);
+        self.state.jump_list.push(pos);
+        if self.state.jump_list.len() > JUMP_LIST_SIZE {
+            self.state.jump_list.remove(0);
+        }
+        self.state.jump_index = self.state.jump_list.len();
+    }
+
+    fn jump_back(&mut self) {
+        if self.state.jump_index == 0 {
+            return;
+        }
+        // Remember where we jumped back from so Ctrl+I can return to it
+        if self.state.jump_index == self.state.jump_list.len() {
+            self.state.jump_list.push(self.textarea.cursor());
+        }
+        self.state.jump_index -= 1;
+        self.jump_to(self.state.jump_list[self.state.jump_index]);
+    }
+
+    fn jump_forward(&mut self) {
+        if self.state.jump_index + 1 >= self.state.jump_list.len() {
+            return;
+        }
+        self.state.jump_index += 1;
+        self.jump_to(self.state.jump_list[self.state.jump_index]);
+    }
+
+    // Jump clamps to the nearest valid position, so marks survive edits that shorten the text
+    fn jump_to(&mut self, (row, col): (usize, usize)) {
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+    }
+
+    fn jump_to_mark(&mut self, name: char, exact: bool) {
+        if let Some(&(row, col)) = self.state.marks.get(&name) {
+            self.push_jump(self.textarea.cursor());
+            if exact {
+                self.jump_to((row, col));
+            } else {
+                let row = row.min(self.textarea.lines().len() - 1);
+                self.move_to_first_non_blank(row);
+            }
+        }
+    }
+
+    fn start_search(&mut self) {
+        self.state.search_query = Some(String::new());
+        self.state.search_origin = self.textarea.cursor();
+    }
+
+    // Updates the search prompt, returning whether it is still open
+    fn handle_search_input(&mut self, input: Input) -> bool {
+        let Some(query) = self.state.search_query.as_mut() else {
+            return false;
+        };
+        match input.key {
+            Key::Enter => {
+                self.state.search_query = None;
+                self.push_jump(self.state.search_origin);
+                return false;
+            }
+            Key::Esc => {
+                self.cancel_search();
+                return false;
+            }
+            Key::Backspace if query.is_empty() => {
+                self.cancel_search();
+                return false;
+            }
+            Key::Backspace => {
+                query.pop();
+            }
+            Key::Char(c) if !input.ctrl && !input.alt => query.push(c),
+            _ => return true,
+        }
+        // Incremental search: jump to the first match after where the search started
+        let query = query.clone();
+        let (row, col) = self.state.search_origin;
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+        if self.textarea.set_search_pattern(&query).is_ok() && !query.is_empty() {
+            self.textarea.search_forward(false);
+        }
+        true
+    }
+
+    fn cancel_search(&mut self) {
+        self.state.search_query = None;
+        let _ = self.textarea.set_search_pattern("");
+        let (row, col) = self.state.search_origin;
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+    }
+
+    fn render_search_prompt(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(query) = &self.state.search_query
+            && area.width > 2
+            && area.height > 0
+        {
+            let prompt = format!("/{}", query);
+            buf.set_stringn(
+                area.x + 1,
+                area.bottom() - 1,
+                prompt,
+                area.width as usize - 2,
+                Style::default(),
+            );
+        }
+    }
+
+    fn repeat_change(&mut self) {
+        if let Some(actions) = self.state.last_change.clone() {
+            for action in actions {
+                self.apply_action(action);
+            }
+        }
+    }
+
     #[rustfmt::skip]
-    fn execute_action(&mut self, action: EditorAction) {
+    fn apply_action(&mut self, action: EditorAction) {
         let mut pending = false;
+        let yanking = matches!(action, EditorAction::Yank(_) | EditorAction::Delete(_));
+        let selecting = matches!(action, EditorAction::SelectRegister(_));
+        // Blank the yank buffer so an operator that finds nothing to copy can be told apart
+        let previous_yank = yanking.then(|| (self.textarea.yank_text(), self.state.yank_type));
+        if yanking {
+            self.textarea.set_yank_text("");
+        }
         match action {
             EditorAction::SetMode(mode) => {
                 match mode {
                     EditorMode::Normal => {
                         self.textarea.cancel_selection();
+                        self.finish_block_insert();
+                        self.state.block_anchor = None;
                     }
                     EditorMode::Visual(vmode) => {
                         match vmode {
                             VisualMode::Char =>  self.textarea.start_selection(),
                             VisualMode::Line => self.textarea.start_line_selection(),
+                            VisualMode::Block => {
+                                self.textarea.cancel_selection();
+                                self.state.block_anchor = Some(self.textarea.cursor());
+                            }
                         }
                     }
-                    EditorMode::Insert | EditorMode::Replace => {}
+                    EditorMode::Replace => self.state.replaced.clear(),
+                    EditorMode::Insert => {}
                 }
                 self.state.mode = mode;
                 self.textarea
@@ -267,9 +1677,23 @@ impl EditorActions for Editor {
             }
             EditorAction::MoveCursor(mvmt) => {
                 match mvmt {
+                    CursorMove::Top | CursorMove::Bottom => {
+                        self.push_jump(self.textarea.cursor());
+                        self.textarea.move_cursor(mvmt);
+                    }
                     _ => self.textarea.move_cursor(mvmt),
                 }
             }
+            EditorAction::FindChar(search, c) => {
+                self.state.last_find = Some((search, c));
+                self.move_to_char(search, c, false);
+            }
+            EditorAction::RepeatFind(reverse) => {
+                if let Some((search, c)) = self.state.last_find {
+                    let search = if reverse { search.reversed() } else { search };
+                    self.move_to_char(search, c, true);
+                }
+            }
             EditorAction::Insert(obj) => {
                 match obj {
                     TextObject::Line => {
@@ -281,6 +1705,9 @@ impl EditorActions for Editor {
                     _ => {}
                 }
             }
+            EditorAction::InsertBlock(edge) => {
+                self.start_block_insert(edge);
+            }
             EditorAction::ApplyInput(input) => {
                 if self.single_line {
                     match input.key {
@@ -308,11 +1735,25 @@ impl EditorActions for Editor {
                         self.textarea.cut();
                         // self.textarea.move_cursor(CursorMove::Jump(current_row as u16, current_col as u16));
                     }
+                    TextObject::PairInner(open, close)
+                        if select_current_pair(&mut self.textarea, open, close, TextObjectModifier::Inner).is_some() =>
+                    {
+                        self.textarea.cut();
+                    }
+                    TextObject::PairAround(open, close)
+                        if select_current_pair(&mut self.textarea, open, close, TextObjectModifier::Around).is_some() =>
+                    {
+                        self.textarea.cut();
+                    }
                     TextObject::Line => {
                         let (current_row, current_col) = select_current_line(&mut self.textarea);
                         self.textarea.cut();
                         self.textarea.move_cursor(CursorMove::Jump(current_row as u16, current_col as u16));
                     }
+                    TextObject::Selection if self.block_bounds().is_some() => {
+                        self.state.yank_type = Some(TextObject::Block);
+                        self.delete_block();
+                    }
                     TextObject::Selection => {
                         self.textarea.cut();
                     }
@@ -321,6 +1762,12 @@ impl EditorActions for Editor {
                         self.textarea.move_cursor(mvmt);
                         self.textarea.cut();
                     }
+                    TextObject::ToChar(search, c) => {
+                        self.state.last_find = Some((search, c));
+                        if self.select_to_char(search, c).is_some() {
+                            self.textarea.cut();
+                        }
+                    }
                     _ => {}
                 }
             },
@@ -338,6 +1785,12 @@ impl EditorActions for Editor {
                     TextObject::ParagraphAround => {
                         let _ = select_current_paragraph(&mut self.textarea, TextObjectModifier::Inner);
                     }
+                    TextObject::PairInner(open, close) => {
+                        let _ = select_current_pair(&mut self.textarea, open, close, TextObjectModifier::Inner);
+                    }
+                    TextObject::PairAround(open, close) => {
+                        let _ = select_current_pair(&mut self.textarea, open, close, TextObjectModifier::Around);
+                    }
                     _ => {}
                 }
                 self.state.mode = EditorMode::Visual(VisualMode::Char);
@@ -352,49 +1805,66 @@ impl EditorActions for Editor {
                         self.textarea.copy();
                         self.textarea.move_cursor(CursorMove::Jump(current_row as u16, current_col as u16));
                     }
+                    TextObject::Selection if self.block_bounds().is_some() => {
+                        self.state.yank_type = Some(TextObject::Block);
+                        if let Some(text) = self.block_text() {
+                            self.textarea.set_yank_text(text);
+                        }
+                    }
                     TextObject::Selection => {
                         if self.state.mode == EditorMode::Visual(VisualMode::Line) {
                             self.state.yank_type = Some(TextObject::Line);
                         }
                         self.textarea.copy();
                     }
+                    TextObject::PairInner(open, close) => {
+                        if let Some((row, col)) = select_current_pair(&mut self.textarea, open, close, TextObjectModifier::Inner) {
+                            self.textarea.copy();
+                            self.textarea.move_cursor(CursorMove::Jump(row as u16, col as u16));
+                        }
+                    }
+                    TextObject::PairAround(open, close) => {
+                        if let Some((row, col)) = select_current_pair(&mut self.textarea, open, close, TextObjectModifier::Around) {
+                            self.textarea.copy();
+                            self.textarea.move_cursor(CursorMove::Jump(row as u16, col as u16));
+                        }
+                    }
+                    TextObject::ToChar(search, c) => {
+                        self.state.last_find = Some((search, c));
+                        let (row, _col) = self.textarea.cursor();
+                        if let Some(start) = self.select_to_char(search, c) {
+                            self.textarea.copy();
+                            self.textarea.move_cursor(CursorMove::Jump(row as u16, start as u16));
+                        }
+                    }
                     _ => {}
                 }
             }
             EditorAction::ReplaceChar(c) => {
-                self.textarea.start_selection();
-                self.textarea.move_cursor(CursorMove::Forward);
-                self.textarea.set_yank_text(c);
-                self.textarea.insert_char(c);
-                self.textarea.move_cursor(CursorMove::Back);
+                if self.char_under_cursor().is_some() {
+                    self.textarea.delete_next_char();
+                    self.textarea.insert_char(c);
+                    self.textarea.move_cursor(CursorMove::Back);
+                }
             }
+            EditorAction::Overwrite(c) => self.overwrite(c),
+            EditorAction::RestoreReplaced => self.restore_replaced(),
             // EditorAction::Copy => {
             //     self.textarea.copy();
             // }
             EditorAction::Paste => {
-                match self.state.yank_type {
-                    Some(TextObject::Line) => {
-                        if self.single_line {
-                            return;
-                        }
-                        let yanked = &self.textarea.yank_text();
-                        self.textarea.set_yank_text(yanked.trim_end());
-                        self.textarea.move_cursor(CursorMove::End);
-                        let _ = self.textarea.insert_newline();
-                        self.textarea.paste();
-                        self.textarea.move_cursor(CursorMove::Head);
-                    }
-                    _ => {
-                        self.textarea.move_cursor(CursorMove::Forward);
-                        self.textarea.paste();
-                    }
-                }
+                // Pasting from a register leaves the unnamed register as it was
+                let yank = self.textarea.yank_text();
+                let yank_type = self.state.yank_type;
+                self.paste();
+                self.textarea.set_yank_text(yank);
+                self.state.yank_type = yank_type;
             }
             EditorAction::Undo => {
-                self.textarea.undo();
+                self.undo();
             }
             EditorAction::Redo => {
-                self.textarea.redo();
+                self.redo();
             }
             // EditorAction::Command(cmd) => match cmd {
             //     EditorCommand::Submit => {}
@@ -405,13 +1875,90 @@ impl EditorActions for Editor {
             }
             EditorAction::MultiAction(actions) => {
                 for act in actions {
-                    self.execute_action(act);
+                    self.apply_action(act);
                 }
             }
+            EditorAction::RepeatChange => {
+                self.repeat_change();
+            }
+            EditorAction::SelectRegister(name) => {
+                self.state.selected_register = Some(name);
+            }
+            EditorAction::JoinLines(obj) => {
+                if !self.single_line {
+                    self.join_lines(obj);
+                }
+            }
+            EditorAction::Indent(obj) => {
+                self.indent_lines(obj);
+            }
+            EditorAction::Dedent(obj) => {
+                self.dedent_lines(obj);
+            }
+            EditorAction::Reflow(obj) => {
+                if !self.single_line {
+                    self.reflow(obj);
+                }
+            }
+            // Macro actions are handled by execute_action before they get here
+            EditorAction::Record | EditorAction::StartRecording(_) | EditorAction::PlayMacro(_) => {}
+            EditorAction::StartSearch => {
+                pending = true;
+                self.start_search();
+                self.state.pending_action = Some(EditorPendingAction::Search);
+            }
+            EditorAction::SearchInput(input) => {
+                pending = self.handle_search_input(input);
+            }
+            EditorAction::SearchNext => {
+                self.push_jump(self.textarea.cursor());
+                self.textarea.search_forward(false);
+            }
+            EditorAction::SearchPrev => {
+                self.push_jump(self.textarea.cursor());
+                self.textarea.search_back(false);
+            }
+            // The link picker, register viewer and spelling suggestions are UI overlays, so the
+            // UI handles these before they get here
+            EditorAction::OpenLinks
+            | EditorAction::ShowRegisters
+            | EditorAction::SuggestSpelling => {}
+            EditorAction::ScrollColumns(cols) => {
+                self.scroll_columns(cols);
+            }
+            EditorAction::ToggleWrap => {
+                self.toggle_wrap();
+            }
+            EditorAction::SetMark(name) => {
+                self.state.marks.insert(name, self.textarea.cursor());
+            }
+            EditorAction::JumpToMark(name, exact) => {
+                self.jump_to_mark(name, exact);
+            }
+            EditorAction::JumpBack => {
+                self.jump_back();
+            }
+            EditorAction::JumpForward => {
+                self.jump_forward();
+            }
         }
 
+        if let Some((text, yank_type)) = previous_yank {
+            if self.textarea.yank_text().is_empty() {
+                // Nothing was copied or cut, so every register keeps what it had
+                self.textarea.set_yank_text(text);
+                self.state.yank_type = yank_type;
+                self.state.selected_register = None;
+            } else {
+                self.store_yank();
+            }
+        }
         if !pending {
             self.state.pending_action = None;
+            // A register only applies to the command right after it
+            if !selecting {
+                self.state.selected_register = None;
+            }
         }
         // match self.state.mode {
         //     EditorMode::Normal => match input {
@@ -445,6 +1992,59 @@ impl EditorActions for Editor {
         //     },
         // }
     }
+}
+
+impl EditorActions for Editor {
+    fn execute_action(&mut self, action: EditorAction) {
+        match action {
+            EditorAction::Record if self.state.macros.is_recording() => {
+                self.state.macros.stop();
+            }
+            EditorAction::Record => {
+                self.state.pending_action = Some(EditorPendingAction::Record);
+            }
+            EditorAction::StartRecording(register) => {
+                self.state.pending_action = None;
+                self.state.macros.start(register);
+            }
+            EditorAction::PlayMacro(register) => {
+                self.state.pending_action = None;
+                for action in self.state.macros.get(register) {
+                    self.execute_action(action);
+                }
+            }
+            action => {
+                let was_typing = is_typing(self.state.mode);
+                let before = (is_edit(&action) || !was_typing && enters_insert(&action))
+                    .then(|| (self.textarea.cursor(), self.textarea.lines().to_vec()));
+                self.state.macros.record(&action);
+                self.record_change(&action);
+                self.apply_action(action);
+                match (was_typing, is_typing(self.state.mode), before) {
+                    (false, true, Some((cursor, before))) => {
+                        self.start_insert_session(cursor, before)
+                    }
+                    (true, true, Some((cursor, before))) => {
+                        self.update_insert_session(cursor, before)
+                    }
+                    (true, false, before) => self.end_insert_session(before),
+                    (_, _, Some((cursor, before))) => self.add_to_cursor_history(cursor, before),
+                    _ => {}
+                }
+                if self.state.mode == EditorMode::Normal {
+                    self.clamp_cursor();
+                }
+                // Recolor the border as the text becomes valid or invalid
+                if self.validator.is_some() {
+                    self.set_editor_style(self.editor_style);
+                }
+                // A change is complete once the editor is back in normal mode
+                if self.state.mode == EditorMode::Normal && self.state.recording_change.is_some() {
+                    self.state.last_change = self.state.recording_change.take();
+                }
+            }
+        }
+    }
     fn set_pending_action(&mut self, pending: Option<EditorPendingAction>) {
         self.state.pending_action = pending;
     }
@@ -461,6 +2061,277 @@ impl Widget for Editor {
 
 impl WidgetRef for Editor {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        self.textarea.clone().render(area, buf);
+        if self.wrap {
+            self.render_wrapped(area, buf);
+        } else {
+            self.update_viewport(area);
+            // Rendering by reference keeps tui-textarea's scroll position between frames
+            (&self.textarea).render(area, buf);
+            self.render_block_selection(area, buf);
+            self.render_spelling(area, buf);
+        }
+        self.render_search_prompt(area, buf);
+    }
+}
+
+fn next_scroll_top(prev_top: u16, cursor: u16, len: u16) -> u16 {
+    if cursor < prev_top {
+        cursor
+    } else if prev_top + len <= cursor {
+        cursor + 1 - len
+    } else {
+        prev_top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jump_to_row_rests_on_last_char_in_normal_mode() {
+        let mut editor = Editor::default().with_content("abc\nabcdef");
+        editor.jump_to_row(0, 5);
+        assert_eq!(editor.get_cursor_pos(), (0, 2));
+        assert_eq!(editor.get_desired_column(), 5);
+    }
+
+    #[test]
+    fn jump_to_row_can_pass_last_char_in_insert_mode() {
+        let mut editor = Editor::default().with_content("abc\nabcdef");
+        editor.execute_action(EditorAction::SetMode(EditorMode::Insert));
+        editor.jump_to_row(0, 5);
+        assert_eq!(editor.get_cursor_pos(), (0, 3));
+    }
+
+    #[test]
+    fn jump_to_row_returns_to_desired_column() {
+        let mut editor = Editor::default().with_content("abc\nabcdef");
+        editor.jump_to_row(0, 5);
+        editor.jump_to_row(1, editor.get_desired_column());
+        assert_eq!(editor.get_cursor_pos(), (1, 5));
+    }
+
+    #[test]
+    fn delete_char_goes_to_the_selected_register() {
+        let mut editor = Editor::default().with_content("abc");
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Head));
+        editor.execute_action(EditorAction::SelectRegister('a'));
+        editor.execute_action(EditorAction::Delete(TextObject::Char));
+        assert_eq!(
+            editor.state.registers.get(&'a').map(|r| r.text.as_str()),
+            Some("a")
+        );
+        assert_eq!(editor.state.selected_register, None);
+        assert!(editor.state.yank_ring.is_empty());
+    }
+
+    #[test]
+    fn register_applies_only_to_the_next_command() {
+        let mut editor = Editor::default().with_content("abc");
+        editor.execute_action(EditorAction::SelectRegister('a'));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Forward));
+        assert_eq!(editor.state.selected_register, None);
+    }
+
+    #[test]
+    fn register_lines_list_unnamed_numbered_then_named() {
+        let mut editor = Editor::default().with_content("one\ntwo");
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Top));
+        editor.execute_action(EditorAction::Yank(TextObject::Line));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Down));
+        editor.execute_action(EditorAction::SelectRegister('b'));
+        editor.execute_action(EditorAction::Yank(TextObject::Line));
+        let lines = editor.register_lines();
+        assert_eq!(
+            lines,
+            ["\"\"  ^Jtwo", "\"1  ^Jtwo", "\"2  one^J", "\"b  ^Jtwo"]
+        );
+    }
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn reflow_lines_fill_up_to_the_width() {
+        let reflowed = reflow_lines(&lines("aaa bbb ccc\nddd"), 7);
+        assert_eq!(reflowed, ["aaa bbb", "ccc ddd"]);
+        let reflowed = reflow_lines(&lines("aaa bbb ccc"), 6);
+        assert_eq!(reflowed, ["aaa", "bbb", "ccc"]);
+    }
+
+    #[test]
+    fn reflow_lines_keep_overlong_words_whole() {
+        let reflowed = reflow_lines(&lines("a abcdefghij b"), 5);
+        assert_eq!(reflowed, ["a", "abcdefghij", "b"]);
+    }
+
+    #[test]
+    fn reflow_lines_keep_blank_lines_between_paragraphs() {
+        let reflowed = reflow_lines(&lines("one\ntwo\n\nthree\nfour"), 80);
+        assert_eq!(reflowed, ["one two", "", "three four"]);
+    }
+
+    #[test]
+    fn reflow_lines_keep_indentation_and_list_markers() {
+        let reflowed = reflow_lines(&lines("  one two three\n- four five six"), 10);
+        assert_eq!(reflowed, ["  one two", "  three", "- four", "  five six"]);
+    }
+
+    #[test]
+    fn visual_reflow_covers_the_selected_rows() {
+        let mut editor = Editor::default()
+            .with_content("one\ntwo\nthree\nfour")
+            .with_text_width(9);
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Top));
+        editor.execute_action(EditorAction::SetMode(EditorMode::Visual(VisualMode::Line)));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Down));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Down));
+        editor.execute_action(EditorAction::MultiAction(vec![
+            EditorAction::Reflow(TextObject::Selection),
+            EditorAction::SetMode(EditorMode::Normal),
+        ]));
+        assert_eq!(editor.get_lines(), ["one two", "three", "four"]);
+        assert_eq!(editor.get_mode(), EditorMode::Normal);
+    }
+
+    fn render(editor: &Editor, width: u16, height: u16) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+        editor.render_ref(buf.area, &mut buf);
+        buf
+    }
+
+    fn row_text(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn wrap_splits_long_lines_and_draws_the_cursor_on_its_slice() {
+        let mut editor = Editor::default()
+            .with_content("abcdefgh\nij")
+            .with_wrap(true);
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Top));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::End));
+        let buf = render(&editor, 4, 3);
+        assert_eq!(row_text(&buf, 0), "abcd");
+        assert_eq!(row_text(&buf, 1), "efgh");
+        assert_eq!(row_text(&buf, 2), "ij  ");
+        assert!(buf[(3, 1)].modifier.contains(Modifier::REVERSED));
+        assert!(!buf[(3, 0)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn wrap_scrolls_to_keep_the_cursor_in_view() {
+        let editor = Editor::default()
+            .with_content("abcdefgh\nij")
+            .with_wrap(true);
+        let buf = render(&editor, 4, 1);
+        assert_eq!(row_text(&buf, 0), "ij  ");
+    }
+
+    #[test]
+    fn click_in_wrapped_view_lands_on_the_slice() {
+        let mut editor = Editor::default()
+            .with_content("abcdefgh\nij")
+            .with_wrap(true);
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Top));
+        render(&editor, 4, 3);
+        editor.on_click(Position::new(1, 1));
+        assert_eq!(editor.get_cursor_pos(), (0, 5));
+        editor.on_click(Position::new(1, 2));
+        assert_eq!(editor.get_cursor_pos(), (1, 1));
+    }
+
+    #[test]
+    fn misspelled_words_are_underlined_and_replaced() {
+        let spellchecker = Spellchecker::new(["the", "cat", "sat"], []);
+        let mut editor = Editor::default()
+            .with_content("the cta sat")
+            .with_spellchecker(Some(spellchecker));
+        let buf = render(&editor, 12, 1);
+        let underlined: Vec<u16> = (0..12)
+            .filter(|&x| buf[(x, 0)].modifier.contains(Modifier::UNDERLINED))
+            .collect();
+        assert_eq!(underlined, [4, 5, 6]);
+
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Jump(0, 5)));
+        let (word, suggestions) = editor.spelling_suggestions().unwrap();
+        assert_eq!(word, "cta");
+        assert_eq!(suggestions, ["cat"]);
+        editor.replace_misspelled_word("cat");
+        assert_eq!(editor.get_lines(), ["the cat sat"]);
+        assert!(editor.spelling_suggestions().is_none());
+        editor.execute_action(EditorAction::Undo);
+        assert_eq!(editor.get_lines(), ["the cta sat"]);
+    }
+
+    #[test]
+    fn cursor_at_line_end_on_last_char_in_normal_mode() {
+        let mut editor = Editor::default().with_content("añb");
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::End));
+        assert_eq!(editor.get_cursor_pos(), (0, 2));
+        assert!(editor.is_cursor_at_line_end());
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Back));
+        assert!(!editor.is_cursor_at_line_end());
+    }
+
+    #[test]
+    fn cursor_at_line_end_after_last_char_in_insert_mode() {
+        let mut editor = Editor::default().with_content("añb");
+        editor.execute_action(EditorAction::SetMode(EditorMode::Insert));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::End));
+        assert!(editor.is_cursor_at_line_end());
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Back));
+        assert!(!editor.is_cursor_at_line_end());
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            editor.execute_action(EditorAction::ApplyInput(Input {
+                key: Key::Char(c),
+                ..Default::default()
+            }));
+        }
+    }
+
+    #[test]
+    fn undo_takes_back_a_whole_insert_session() {
+        let mut editor = Editor::default().with_content("abc");
+        editor.execute_action(EditorAction::SetMode(EditorMode::Insert));
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::End));
+        type_text(&mut editor, "xyz");
+        editor.execute_action(EditorAction::SetMode(EditorMode::Normal));
+        assert_eq!(editor.get_lines(), ["abcxyz"]);
+        editor.execute_action(EditorAction::Undo);
+        assert_eq!(editor.get_lines(), ["abc"]);
+        editor.execute_action(EditorAction::Redo);
+        assert_eq!(editor.get_lines(), ["abcxyz"]);
+    }
+
+    #[test]
+    fn undo_takes_back_the_change_that_entered_insert_mode() {
+        let mut editor = Editor::default().with_content("one two");
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Head));
+        editor.execute_action(EditorAction::MultiAction(vec![
+            EditorAction::Delete(TextObject::WordInner),
+            EditorAction::SetMode(EditorMode::Insert),
+        ]));
+        type_text(&mut editor, "six");
+        editor.execute_action(EditorAction::SetMode(EditorMode::Normal));
+        assert_eq!(editor.get_lines(), ["six two"]);
+        editor.execute_action(EditorAction::Undo);
+        assert_eq!(editor.get_lines(), ["one two"]);
+    }
+
+    #[test]
+    fn empty_insert_session_leaves_no_undo_step() {
+        let mut editor = Editor::default().with_content("abc");
+        editor.execute_action(EditorAction::MoveCursor(CursorMove::Head));
+        editor.execute_action(EditorAction::Delete(TextObject::Char));
+        editor.execute_action(EditorAction::SetMode(EditorMode::Insert));
+        editor.execute_action(EditorAction::SetMode(EditorMode::Normal));
+        editor.execute_action(EditorAction::Undo);
+        assert_eq!(editor.get_lines(), ["abc"]);
     }
 }
@@ -1,8 +1,9 @@
 use tui_textarea::{CursorMove, Input, Key};
 
 use super::{
-    EditorAction, EditorMode, EditorPendingAction, TextObject, TextObjectModifier, VisualMode,
-    is_movement_key, match_movement_key,
+    BlockEdge, EditorAction, EditorMode, EditorOperator, EditorPendingAction, TextObject,
+    TextObjectModifier, VisualMode, is_char_search_key, is_movement_key, is_pair_key,
+    match_char_search_key, match_movement_key, match_pair_key,
 };
 
 pub fn handle_input(input: Input, mode: EditorMode) -> Option<EditorAction> {
@@ -127,6 +128,48 @@ fn handle_normal_mode_input(input: Input) -> Option<EditorAction> {
             alt: false,
             shift: false,
         } => Some(EditorAction::Redo),
+        Input {
+            key: Key::Char('.'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::RepeatChange),
+        Input {
+            key: Key::Char('J'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::JoinLines(TextObject::Line)),
+        Input {
+            key: Key::Char('>'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::Pending(EditorPendingAction::Indent)),
+        Input {
+            key: Key::Char('<'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::Pending(EditorPendingAction::Dedent)),
+        Input {
+            key: Key::Char('"'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::Pending(EditorPendingAction::Register)),
+        Input {
+            key: Key::Char('q'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::Record),
+        Input {
+            key: Key::Char('@'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::Pending(EditorPendingAction::PlayMacro)),
         Input {
             key: Key::Char('s'),
             ctrl: false,
@@ -145,6 +188,96 @@ fn handle_normal_mode_input(input: Input) -> Option<EditorAction> {
             alt: false,
             ..
         } => Some(EditorAction::SetMode(EditorMode::Visual(VisualMode::Line))),
+        Input {
+            key: Key::Char('v'),
+            ctrl: true,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::SetMode(EditorMode::Visual(VisualMode::Block))),
+
+        Input {
+            key: Key::Char('g'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::Pending(EditorPendingAction::Goto)),
+        Input {
+            key: Key::Char('z'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::Pending(EditorPendingAction::Scroll)),
+
+        // Marks and jumps
+        Input {
+            key: Key::Char('m'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::Pending(EditorPendingAction::SetMark)),
+        Input {
+            key: Key::Char('`'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::Pending(EditorPendingAction::JumpToMark(true))),
+        Input {
+            key: Key::Char('\''),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::Pending(EditorPendingAction::JumpToMark(
+            false,
+        ))),
+        Input {
+            key: Key::Char('o'),
+            ctrl: true,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::JumpBack),
+        // Terminals report Ctrl+I as Tab
+        Input {
+            key: Key::Tab,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::JumpForward),
+
+        // Text search
+        Input {
+            key: Key::Char('/'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::StartSearch),
+        Input {
+            key: Key::Char('n'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(EditorAction::SearchNext),
+        Input {
+            key: Key::Char('N'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::SearchPrev),
+
+        // Character search
+        Input { .. } if is_char_search_key(&input) => match_char_search_key(&input)
+            .map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, None))),
+        Input {
+            key: Key::Char(';'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::RepeatFind(false)),
+        Input {
+            key: Key::Char(','),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(EditorAction::RepeatFind(true)),
 
         // Cursor movements
         Input { .. } if is_movement_key(&input) => {
@@ -178,6 +311,9 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                 alt: false,
                 shift: false,
             } => Some(EditorAction::Delete(TextObject::Line)),
+            Input { .. } if is_char_search_key(&input) => {
+                match_char_search_key(&input).map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, Some(EditorOperator::Delete))))
+            }
             Input { .. } if is_movement_key(&input) => {
                 match_movement_key(&input).map(|mvmt| EditorAction::Delete(TextObject::To(mvmt)))
             }
@@ -202,6 +338,10 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                 TextObjectModifier::Inner => Some(EditorAction::Delete(TextObject::ParagraphInner)),
                 TextObjectModifier::Around => Some(EditorAction::Delete(TextObject::ParagraphAround)),
             },
+            Input { .. } if is_pair_key(&input) => match_pair_key(&input).map(|(open, close)| match modifier {
+                TextObjectModifier::Inner => EditorAction::Delete(TextObject::PairInner(open, close)),
+                TextObjectModifier::Around => EditorAction::Delete(TextObject::PairAround(open, close)),
+            }),
             _ => None,
         },
         EditorPendingAction::Select(None) => match input {
@@ -256,6 +396,10 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                 TextObjectModifier::Inner => Some(EditorAction::Select(TextObject::ParagraphInner)),
                 TextObjectModifier::Around => Some(EditorAction::Select(TextObject::ParagraphAround)),
             },
+            Input { .. } if is_pair_key(&input) => match_pair_key(&input).map(|(open, close)| match modifier {
+                TextObjectModifier::Inner => EditorAction::Select(TextObject::PairInner(open, close)),
+                TextObjectModifier::Around => EditorAction::Select(TextObject::PairAround(open, close)),
+            }),
             _ => None,
         },
         EditorPendingAction::Yank(None) => match input {
@@ -279,6 +423,9 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                 alt: false,
                 shift: false,
             } => Some(EditorAction::Yank(TextObject::Line)),
+            Input { .. } if is_char_search_key(&input) => {
+                match_char_search_key(&input).map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, Some(EditorOperator::Yank))))
+            }
             Input { .. } if is_movement_key(&input) => {
                 match_movement_key(&input).map(|mvmt| EditorAction::Yank(TextObject::To(mvmt)))
             }
@@ -303,6 +450,10 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                 TextObjectModifier::Inner => Some(EditorAction::Yank(TextObject::ParagraphInner)),
                 TextObjectModifier::Around => Some(EditorAction::Yank(TextObject::ParagraphAround)),
             },
+            Input { .. } if is_pair_key(&input) => match_pair_key(&input).map(|(open, close)| match modifier {
+                TextObjectModifier::Inner => EditorAction::Yank(TextObject::PairInner(open, close)),
+                TextObjectModifier::Around => EditorAction::Yank(TextObject::PairAround(open, close)),
+            }),
             _ => None,
         },
         EditorPendingAction::Change(None) => match input {
@@ -329,6 +480,9 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                 EditorAction::Delete(TextObject::Line),
                 EditorAction::SetMode(EditorMode::Insert),
             ])),
+            Input { .. } if is_char_search_key(&input) => {
+                match_char_search_key(&input).map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, Some(EditorOperator::Change))))
+            }
             Input { .. } if is_movement_key(&input) => {
                 match_movement_key(&input).map(|mvmt| EditorAction::MultiAction(vec![
                     EditorAction::Delete(TextObject::To(mvmt)),
@@ -368,6 +522,16 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
                     EditorAction::SetMode(EditorMode::Insert),
                 ])),
             },
+            Input { .. } if is_pair_key(&input) => match_pair_key(&input).map(|(open, close)| match modifier {
+                TextObjectModifier::Inner => EditorAction::MultiAction(vec![
+                    EditorAction::Delete(TextObject::PairInner(open, close)),
+                    EditorAction::SetMode(EditorMode::Insert),
+                ]),
+                TextObjectModifier::Around => EditorAction::MultiAction(vec![
+                    EditorAction::Delete(TextObject::PairAround(open, close)),
+                    EditorAction::SetMode(EditorMode::Insert),
+                ]),
+            }),
             _ => None,
         },
         EditorPendingAction::ReplaceChar => match input {
@@ -379,6 +543,192 @@ pub fn handle_pending_action_input(input: Input, pending: EditorPendingAction) -
             } => Some(EditorAction::ReplaceChar(c)),
             _ => None,
         },
+        EditorPendingAction::Search => Some(EditorAction::SearchInput(input)),
+        EditorPendingAction::SetMark => match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } if c.is_ascii_alphabetic() => Some(EditorAction::SetMark(c)),
+            _ => None,
+        },
+        EditorPendingAction::JumpToMark(exact) => match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } if c.is_ascii_alphabetic() => Some(EditorAction::JumpToMark(c, exact)),
+            _ => None,
+        },
+        EditorPendingAction::Indent => match input {
+            Input {
+                key: Key::Char('>'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::Indent(TextObject::Line)),
+            _ => None,
+        },
+        EditorPendingAction::Dedent => match input {
+            Input {
+                key: Key::Char('<'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::Dedent(TextObject::Line)),
+            _ => None,
+        },
+        EditorPendingAction::Record => match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } if c.is_ascii_alphanumeric() => Some(EditorAction::StartRecording(c)),
+            _ => None,
+        },
+        EditorPendingAction::PlayMacro => match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } if c.is_ascii_alphanumeric() || c == '@' => Some(EditorAction::PlayMacro(c)),
+            _ => None,
+        },
+        EditorPendingAction::Goto => match input {
+            Input {
+                key: Key::Char('g'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::MoveCursor(CursorMove::Top)),
+            Input {
+                key: Key::Char('q'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::Pending(EditorPendingAction::Reflow(None))),
+            Input {
+                key: Key::Char('x'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::OpenLinks),
+            Input {
+                key: Key::Char('"'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::ShowRegisters),
+            _ => None,
+        },
+        EditorPendingAction::VisualGoto => match input {
+            Input {
+                key: Key::Char('g'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::MoveCursor(CursorMove::Top)),
+            Input {
+                key: Key::Char('q'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Reflow(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            _ => None,
+        },
+        EditorPendingAction::Scroll => match input {
+            Input {
+                key: Key::Char('l'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::ScrollColumns(1)),
+            Input {
+                key: Key::Char('h'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::ScrollColumns(-1)),
+            Input {
+                key: Key::Char('w'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::ToggleWrap),
+            Input {
+                key: Key::Char('='),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::SuggestSpelling),
+            _ => None,
+        },
+        EditorPendingAction::Reflow(None) => match input {
+            Input {
+                key: Key::Char('i'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::Pending(EditorPendingAction::Reflow(Some(TextObjectModifier::Inner)))),
+            Input {
+                key: Key::Char('a'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::Pending(EditorPendingAction::Reflow(Some(TextObjectModifier::Around)))),
+            Input {
+                key: Key::Char('q'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::Reflow(TextObject::Line)),
+            _ => None,
+        },
+        EditorPendingAction::Reflow(Some(modifier)) => match input {
+            Input {
+                key: Key::Char('p'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => match modifier {
+                TextObjectModifier::Inner => Some(EditorAction::Reflow(TextObject::ParagraphInner)),
+                TextObjectModifier::Around => Some(EditorAction::Reflow(TextObject::ParagraphAround)),
+            },
+            _ => None,
+        },
+        EditorPendingAction::Register => match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } if c.is_ascii_alphanumeric() || c == '"' => Some(EditorAction::SelectRegister(c)),
+            _ => None,
+        },
+        EditorPendingAction::FindChar(search, operator) => match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } => match operator {
+                None => Some(EditorAction::FindChar(search, c)),
+                Some(EditorOperator::Delete) => Some(EditorAction::Delete(TextObject::ToChar(search, c))),
+                Some(EditorOperator::Change) => Some(EditorAction::MultiAction(vec![
+                    EditorAction::Delete(TextObject::ToChar(search, c)),
+                    EditorAction::SetMode(EditorMode::Insert),
+                ])),
+                Some(EditorOperator::Yank) => Some(EditorAction::Yank(TextObject::ToChar(search, c))),
+            },
+            _ => None,
+        },
     }
 }
 
@@ -410,6 +760,45 @@ pub fn handle_visual_mode_input(input: Input, mode: VisualMode) -> Option<Editor
                 EditorAction::Delete(TextObject::Selection),
                 EditorAction::SetMode(EditorMode::Normal),
             ])),
+            Input {
+                key: Key::Char('J'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::JoinLines(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('>'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Indent(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('<'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Dedent(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('"'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::Pending(EditorPendingAction::Register)),
+            Input {
+                key: Key::Char('g'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::Pending(EditorPendingAction::VisualGoto)),
             Input {
                 key: Key::Char('y'),
                 ctrl: false,
@@ -428,6 +817,20 @@ pub fn handle_visual_mode_input(input: Input, mode: VisualMode) -> Option<Editor
                 EditorAction::Delete(TextObject::Selection),
                 EditorAction::SetMode(EditorMode::Insert),
             ])),
+            Input { .. } if is_char_search_key(&input) => match_char_search_key(&input)
+                .map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, None))),
+            Input {
+                key: Key::Char(';'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::RepeatFind(false)),
+            Input {
+                key: Key::Char(','),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::RepeatFind(true)),
             Input { .. } if is_movement_key(&input) => {
                 match_movement_key(&input).map(|mvmt| EditorAction::MoveCursor(mvmt))
             }
@@ -444,6 +847,45 @@ pub fn handle_visual_mode_input(input: Input, mode: VisualMode) -> Option<Editor
                 EditorAction::Delete(TextObject::Selection),
                 EditorAction::SetMode(EditorMode::Normal),
             ])),
+            Input {
+                key: Key::Char('J'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::JoinLines(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('>'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Indent(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('<'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Dedent(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('"'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::Pending(EditorPendingAction::Register)),
+            Input {
+                key: Key::Char('g'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::Pending(EditorPendingAction::VisualGoto)),
             Input {
                 key: Key::Char('y'),
                 ctrl: false,
@@ -462,11 +904,118 @@ pub fn handle_visual_mode_input(input: Input, mode: VisualMode) -> Option<Editor
                 EditorAction::Delete(TextObject::Selection),
                 EditorAction::SetMode(EditorMode::Insert),
             ])),
+            Input { .. } if is_char_search_key(&input) => match_char_search_key(&input)
+                .map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, None))),
+            Input {
+                key: Key::Char(';'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::RepeatFind(false)),
+            Input {
+                key: Key::Char(','),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::RepeatFind(true)),
             Input { .. } if is_movement_key(&input) => {
                 match_movement_key(&input).map(|mvmt| EditorAction::MoveCursor(mvmt))
             }
             _ => None,
         },
+        VisualMode::Block => match input {
+            Input { key: Key::Esc, .. } => Some(EditorAction::SetMode(EditorMode::Normal)),
+            Input {
+                key: Key::Char('d' | 'x'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Delete(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('J'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::JoinLines(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('>'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Indent(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('<'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Dedent(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('"'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::Pending(EditorPendingAction::Register)),
+            Input {
+                key: Key::Char('y'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Yank(TextObject::Selection),
+                EditorAction::SetMode(EditorMode::Normal),
+            ])),
+            Input {
+                key: Key::Char('c'),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            } => Some(EditorAction::MultiAction(vec![
+                EditorAction::Delete(TextObject::Selection),
+                EditorAction::InsertBlock(BlockEdge::Start),
+            ])),
+            Input {
+                key: Key::Char('I'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::InsertBlock(BlockEdge::Start)),
+            Input {
+                key: Key::Char('A'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::InsertBlock(BlockEdge::End)),
+            Input { .. } if is_char_search_key(&input) => match_char_search_key(&input)
+                .map(|search| EditorAction::Pending(EditorPendingAction::FindChar(search, None))),
+            Input {
+                key: Key::Char(';'),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::RepeatFind(false)),
+            Input {
+                key: Key::Char(','),
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(EditorAction::RepeatFind(true)),
+            Input { .. } if is_movement_key(&input) => {
+                match_movement_key(&input).map(EditorAction::MoveCursor)
+            }
+            _ => None,
+        },
     }
 }
 
@@ -483,10 +1032,11 @@ pub fn handle_replace_mode_input(input: Input) -> Option<EditorAction> {
         ])),
         Input {
             key: Key::Char(c), ..
-        } => Some(EditorAction::MultiAction(vec![
-            EditorAction::ReplaceChar(c),
-            EditorAction::MoveCursor(CursorMove::Forward),
-        ])),
+        } => Some(EditorAction::Overwrite(c)),
+        Input {
+            key: Key::Backspace,
+            ..
+        } => Some(EditorAction::RestoreReplaced),
         _ => None,
     }
 }
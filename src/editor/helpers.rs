@@ -4,7 +4,7 @@ use ratatui::{
 };
 use tui_textarea::{CursorMove, Input, Key};
 
-use super::EditorMode;
+use super::{CharSearch, EditorMode};
 
 pub fn is_movement_key(input: &Input) -> bool {
     matches!(
@@ -134,6 +134,103 @@ pub fn match_movement_key(input: &Input) -> Option<CursorMove> {
     }
 }
 
+pub fn is_char_search_key(input: &Input) -> bool {
+    matches!(
+        input,
+        Input {
+            key: Key::Char('f' | 't'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } | Input {
+            key: Key::Char('F' | 'T'),
+            ctrl: false,
+            alt: false,
+            ..
+        }
+    )
+}
+
+pub fn match_char_search_key(input: &Input) -> Option<CharSearch> {
+    match input {
+        Input {
+            key: Key::Char('f'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(CharSearch::Find),
+        Input {
+            key: Key::Char('t'),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        } => Some(CharSearch::Till),
+        Input {
+            key: Key::Char('F'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(CharSearch::FindBack),
+        Input {
+            key: Key::Char('T'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(CharSearch::TillBack),
+        _ => None,
+    }
+}
+
+pub fn is_pair_key(input: &Input) -> bool {
+    matches!(
+        input,
+        Input {
+            key: Key::Char(
+                '"' | '\'' | '`' | '(' | ')' | 'b' | '[' | ']' | '{' | '}' | 'B' | '<' | '>'
+            ),
+            ctrl: false,
+            alt: false,
+            ..
+        }
+    )
+}
+
+pub fn match_pair_key(input: &Input) -> Option<(char, char)> {
+    match input {
+        Input {
+            key: Key::Char(c @ ('"' | '\'' | '`')),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some((*c, *c)),
+        Input {
+            key: Key::Char('(' | ')' | 'b'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(('(', ')')),
+        Input {
+            key: Key::Char('[' | ']'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(('[', ']')),
+        Input {
+            key: Key::Char('{' | '}' | 'B'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(('{', '}')),
+        Input {
+            key: Key::Char('<' | '>'),
+            ctrl: false,
+            alt: false,
+            ..
+        } => Some(('<', '>')),
+        _ => None,
+    }
+}
+
 pub fn cursor_style(mode: EditorMode, is_active: bool) -> Style {
     if !is_active {
         return Style::default();
@@ -152,14 +249,18 @@ pub fn create_block<'a>(
     title: Option<String>,
     is_active: bool,
     borders: Borders,
-    // is_valid: Option<bool>
+    is_valid: Option<bool>,
 ) -> Block<'a> {
     let mut style = Style::default();
     if !is_active {
         style = style.add_modifier(Modifier::DIM);
     }
 
-    let mut border_style = Style::default();
+    let mut border_style = match is_valid {
+        Some(true) => Style::default().fg(Color::LightGreen),
+        Some(false) => Style::default().fg(Color::LightRed),
+        None => Style::default(),
+    };
     if !is_active {
         border_style = border_style.add_modifier(Modifier::DIM);
     }
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use super::EditorAction;
+
+#[derive(Default)]
+pub struct MacroRecorder {
+    macros: HashMap<char, Vec<EditorAction>>,
+    recording: Option<(char, Vec<EditorAction>)>,
+    last_played: Option<char>,
+}
+
+impl MacroRecorder {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn start(&mut self, register: char) {
+        self.recording = Some((register, Vec::new()));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((register, actions)) = self.recording.take() {
+            self.macros.insert(register, actions);
+        }
+    }
+
+    pub fn record(&mut self, action: &EditorAction) {
+        // Pending actions only carry key prefixes; the action they resolve to is recorded instead
+        if let EditorAction::Pending(_) = action {
+            return;
+        }
+        if let Some((_, actions)) = self.recording.as_mut() {
+            actions.push(action.clone());
+        }
+    }
+
+    pub fn get(&mut self, register: char) -> Vec<EditorAction> {
+        let register = if register == '@' {
+            match self.last_played {
+                Some(register) => register,
+                None => return Vec::new(),
+            }
+        } else {
+            register
+        };
+        self.last_played = Some(register);
+        self.macros.get(&register).cloned().unwrap_or_default()
+    }
+}
@@ -9,8 +9,31 @@ pub enum TextObject {
     Line,
     ParagraphInner,
     ParagraphAround,
+    PairInner(char, char),
+    PairAround(char, char),
     Selection,
+    Block,
     To(CursorMove),
+    ToChar(CharSearch, char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharSearch {
+    Find,
+    Till,
+    FindBack,
+    TillBack,
+}
+
+impl CharSearch {
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Find => Self::FindBack,
+            Self::Till => Self::TillBack,
+            Self::FindBack => Self::Find,
+            Self::TillBack => Self::Till,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -19,11 +42,17 @@ pub enum TextObjectModifier {
     Around,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum BlockEdge {
+    Start,
+    End,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VisualMode {
     Char,
     Line,
-    // Block,
+    Block,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +63,14 @@ pub enum EditorMode {
     Visual(VisualMode),
 }
 
+// A popup that can fill in a field instead of typing its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPicker {
+    Date,
+    Time,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EditorStyle {
     Active,
     Inactive,
@@ -47,6 +84,7 @@ impl fmt::Display for EditorMode {
             Self::Normal => write!(f, "NORMAL"),
             Self::Insert => write!(f, "INSERT"),
             Self::Replace => write!(f, "REPLACE"),
+            Self::Visual(VisualMode::Block) => write!(f, "VISUAL BLOCK"),
             Self::Visual(_) => write!(f, "VISUAL"),
         }
     }
@@ -57,9 +95,16 @@ mod composite;
 mod editor;
 mod handlers;
 mod helpers;
+mod macros;
+mod validators;
 
-pub use actions::{EditorAction, EditorActions, EditorPendingAction};
+pub use actions::{EditorAction, EditorActions, EditorOperator, EditorPendingAction};
 pub use composite::CompositeEditor;
 pub use editor::Editor;
 pub use handlers::{handle_input, handle_pending_action_input};
-pub use helpers::{create_block, cursor_style, is_movement_key, match_movement_key};
+pub use helpers::{
+    create_block, cursor_style, is_char_search_key, is_movement_key, is_pair_key,
+    match_char_search_key, match_movement_key, match_pair_key,
+};
+pub use macros::MacroRecorder;
+pub use validators::{date_validator, time_validator};
@@ -0,0 +1,44 @@
+use chrono::{
+    NaiveDate, NaiveTime,
+    format::{ParseError, ParseErrorKind},
+};
+use tui_textarea::TextArea;
+
+fn text(textarea: &TextArea) -> String {
+    textarea.lines().join("\n").trim().to_string()
+}
+
+fn describe(error: ParseError, what: &str, example: &str) -> String {
+    match error.kind() {
+        ParseErrorKind::OutOfRange | ParseErrorKind::Impossible => format!("No such {}", what),
+        _ => format!("Expected a {} like {}", what, example),
+    }
+}
+
+// Accepts dates in the given chrono format, e.g. %m/%d/%Y
+pub fn date_validator(
+    format: &str,
+    example: &str,
+) -> impl Fn(&TextArea) -> Result<(), String> + use<> {
+    let format = format.to_string();
+    let example = example.to_string();
+    move |textarea: &TextArea| {
+        NaiveDate::parse_from_str(&text(textarea), &format)
+            .map(|_| ())
+            .map_err(|e| describe(e, "date", &example))
+    }
+}
+
+// Accepts times in the given chrono format, e.g. %I:%M %p
+pub fn time_validator(
+    format: &str,
+    example: &str,
+) -> impl Fn(&TextArea) -> Result<(), String> + use<> {
+    let format = format.to_string();
+    let example = example.to_string();
+    move |textarea: &TextArea| {
+        NaiveTime::parse_from_str(&text(textarea), &format)
+            .map(|_| ())
+            .map_err(|e| describe(e, "time", &example))
+    }
+}
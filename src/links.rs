@@ -0,0 +1,135 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::Widget,
+};
+
+use crate::popup::Popup;
+
+const URL_SCHEMES: [&str; 2] = ["https://", "http://"];
+
+// Finds http(s) URLs in the text, in order and without duplicates
+pub fn find_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`')) {
+        let Some(start) = URL_SCHEMES
+            .iter()
+            .filter_map(|scheme| word.find(scheme))
+            .min()
+        else {
+            continue;
+        };
+        let url = trim_url_end(&word[start..]);
+        if URL_SCHEMES
+            .iter()
+            .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme))
+            && !urls.iter().any(|existing| existing == url)
+        {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+// Drops punctuation that usually ends a sentence or wraps a link rather than belonging to it.
+// A closing bracket stays when the URL opens it too, as in /wiki/Foo_(bar)
+fn trim_url_end(mut url: &str) -> &str {
+    loop {
+        let Some(last) = url.chars().last() else {
+            return url;
+        };
+        let opener = match last {
+            ')' => '(',
+            ']' => '[',
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' => {
+                url = &url[..url.len() - 1];
+                continue;
+            }
+            _ => return url,
+        };
+        let opened = url.matches(opener).count();
+        let closed = url.matches(last).count();
+        if opened >= closed {
+            return url;
+        }
+        url = &url[..url.len() - 1];
+    }
+}
+
+pub struct LinkPicker {
+    urls: Vec<String>,
+    selected: usize,
+}
+
+impl LinkPicker {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self { urls, selected: 0 }
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.urls.get(self.selected).map(String::as_str)
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.urls.len().saturating_sub(1));
+    }
+}
+
+impl Widget for &LinkPicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .urls
+            .iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let style = if i == self.selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                Line::styled(url.clone(), style)
+            })
+            .collect();
+        let popup = Popup::new(Text::from(lines))
+            .with_title("Open link (Enter)")
+            .with_size(70, 40)
+            .with_wrap(false);
+        let height = popup.inner_height(area) as usize;
+        let scroll = (self.selected + 1).saturating_sub(height);
+        popup.with_scroll(scroll as u16).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_punctuation_is_dropped() {
+        let urls = find_urls("See https://example.com/a. Or https://example.com/b, maybe!");
+        assert_eq!(urls, ["https://example.com/a", "https://example.com/b"]);
+    }
+
+    #[test]
+    fn balanced_brackets_are_kept() {
+        let urls = find_urls("https://en.wikipedia.org/wiki/Foo_(bar) is the page");
+        assert_eq!(urls, ["https://en.wikipedia.org/wiki/Foo_(bar)"]);
+    }
+
+    #[test]
+    fn wrapping_brackets_are_dropped() {
+        let urls = find_urls("(see https://example.com/docs) and [https://example.com/x].");
+        assert_eq!(urls, ["https://example.com/docs", "https://example.com/x"]);
+    }
+
+    #[test]
+    fn wrapped_url_keeps_its_own_brackets() {
+        let urls = find_urls("(https://en.wikipedia.org/wiki/Foo_(bar)).");
+        assert_eq!(urls, ["https://en.wikipedia.org/wiki/Foo_(bar)"]);
+    }
+}
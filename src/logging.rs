@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::EnvFilter;
+
+const LOG_FILE_PREFIX: &str = "ticktui";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+fn get_log_dir() -> Option<PathBuf> {
+    let mut path = dirs::data_local_dir()?;
+    path.push("ticktui");
+    path.push("logs");
+    Some(path)
+}
+
+// Logs to a daily rotating file; the level can be set with TICKTUI_LOG (e.g. TICKTUI_LOG=debug)
+pub fn init() -> Result<WorkerGuard> {
+    let dir = get_log_dir().context("could not determine the data directory")?;
+    std::fs::create_dir_all(&dir)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let filter = EnvFilter::try_from_env("TICKTUI_LOG").unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_ansi(false)
+        .init();
+    Ok(guard)
+}
+
+// Returns the last `count` lines of the most recent log file
+pub fn read_recent(count: usize) -> Vec<String> {
+    let Some(dir) = get_log_dir() else {
+        return Vec::new();
+    };
+    let latest = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .max();
+    let Some(content) = latest.and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
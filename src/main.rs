@@ -1,7 +1,14 @@
 mod app;
+mod config;
+mod datepicker;
 // mod auth;
 mod editor;
+mod links;
+mod logging;
+mod popup;
+mod spelling;
 mod term;
+mod timepicker;
 mod ui;
 // mod utils;
 
@@ -11,12 +18,15 @@ use anyhow::Result;
 
 #[tokio::main]
 async fn main() {
+    let _log_guard = logging::init().ok();
     // if let Some((client_id, client_secret)) = auth::get_client_id() {
     //     if let Some(access_token) = auth::get_access_token(client_id, client_secret).await {
     //         let _ = run(access_token).await;
     //     }
     // }
-    let _ = run().await;
+    if let Err(e) = run().await {
+        tracing::error!("{:?}", e);
+    }
 }
 
 // async fn run(access_token: ticks::AccessToken) -> Result<()> {
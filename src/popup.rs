@@ -0,0 +1,118 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Text,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+#[derive(Debug, Clone, Copy)]
+enum PopupSize {
+    Percent(u16, u16),
+    Fixed(u16, u16),
+}
+
+pub struct Popup<'a> {
+    title: Option<String>,
+    content: Text<'a>,
+    size: PopupSize,
+    scroll: u16,
+    wrap: bool,
+}
+
+impl<'a> Popup<'a> {
+    pub fn new<T: Into<Text<'a>>>(content: T) -> Self {
+        Self {
+            title: None,
+            content: content.into(),
+            size: PopupSize::Percent(60, 50),
+            scroll: 0,
+            wrap: true,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn with_size(mut self, percent_x: u16, percent_y: u16) -> Self {
+        self.size = PopupSize::Percent(percent_x.min(100), percent_y.min(100));
+        self
+    }
+
+    // A fixed size in cells, for content that doesn't stretch like a calendar
+    pub fn with_fixed_size(mut self, width: u16, height: u16) -> Self {
+        self.size = PopupSize::Fixed(width, height);
+        self
+    }
+
+    pub fn with_scroll(mut self, scroll: u16) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    // The area the popup covers when rendered over `area`
+    pub fn area(&self, area: Rect) -> Rect {
+        match self.size {
+            PopupSize::Percent(percent_x, percent_y) => popup_rect(percent_x, percent_y, area),
+            PopupSize::Fixed(width, height) => {
+                let width = width.min(area.width);
+                let height = height.min(area.height);
+                Rect::new(
+                    area.x + (area.width - width) / 2,
+                    area.y + (area.height - height) / 2,
+                    width,
+                    height,
+                )
+            }
+        }
+    }
+
+    // The number of content lines visible inside the border
+    pub fn inner_height(&self, area: Rect) -> u16 {
+        self.area(area).height.saturating_sub(2)
+    }
+}
+
+impl Widget for Popup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup_area = self.area(area);
+        let mut block = Block::default().borders(Borders::ALL);
+        if let Some(title) = self.title {
+            block = block.title(title);
+        }
+        let mut paragraph = Paragraph::new(self.content)
+            .block(block)
+            .scroll((self.scroll, 0));
+        if self.wrap {
+            paragraph = paragraph.wrap(Wrap { trim: false });
+        }
+        Clear.render(popup_area, buf);
+        paragraph.render(popup_area, buf);
+    }
+}
+
+fn popup_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::Widget,
+};
+
+use crate::{config::SpellConfig, popup::Popup};
+
+const SYSTEM_WORD_LIST: &str = "/usr/share/dict/words";
+const MAX_SUGGESTIONS: usize = 10;
+
+// Checks words against a plain word list, one word per line
+pub struct Spellchecker {
+    words: HashSet<String>,
+    ignore: HashSet<String>,
+}
+
+impl Spellchecker {
+    pub fn new<'a>(
+        words: impl IntoIterator<Item = &'a str>,
+        ignore: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let lower = |word: &str| word.trim().to_lowercase();
+        Self {
+            words: words.into_iter().map(lower).collect(),
+            ignore: ignore.into_iter().map(lower).collect(),
+        }
+    }
+
+    // Reads ticktui/dictionaries/<language>.txt from the config dir, or the system word list if
+    // there is none. Returns None when spellchecking is off or no word list can be read
+    pub fn load(config: &SpellConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let content = get_word_list_path(&config.language)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .or_else(|| std::fs::read_to_string(SYSTEM_WORD_LIST).ok());
+        let Some(content) = content else {
+            tracing::warn!("no word list for {}, spellcheck is off", config.language);
+            return None;
+        };
+        Some(Self::new(
+            content.lines(),
+            config.ignore.iter().map(String::as_str),
+        ))
+    }
+
+    pub fn is_misspelled(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        !self.words.contains(&word) && !self.ignore.contains(&word)
+    }
+
+    // Char ranges of the misspelled words in a line
+    pub fn misspelled(&self, line: &str) -> Vec<(usize, usize)> {
+        let chars: Vec<char> = line.chars().collect();
+        word_ranges(&chars)
+            .into_iter()
+            .filter(|&(start, end)| {
+                self.is_misspelled(&chars[start..end].iter().collect::<String>())
+            })
+            .collect()
+    }
+
+    // Known words one edit away: a deleted, swapped, replaced or inserted letter
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let word = word.to_lowercase();
+        let chars: Vec<char> = word.chars().collect();
+        let mut candidates = Vec::new();
+        for i in 0..=chars.len() {
+            if i < chars.len() {
+                let mut deleted = chars.clone();
+                deleted.remove(i);
+                candidates.push(deleted);
+            }
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                candidates.push(swapped);
+            }
+            for c in 'a'..='z' {
+                if i < chars.len() {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    candidates.push(replaced);
+                }
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                candidates.push(inserted);
+            }
+        }
+        let mut suggestions: Vec<String> = candidates
+            .into_iter()
+            .map(|candidate| candidate.into_iter().collect::<String>())
+            .filter(|candidate| *candidate != word && self.words.contains(candidate))
+            .collect();
+        suggestions.sort();
+        suggestions.dedup();
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+}
+
+fn get_word_list_path(language: &str) -> Option<PathBuf> {
+    let mut path = dirs::config_dir()?;
+    path.push("ticktui");
+    path.push("dictionaries");
+    path.push(format!("{}.txt", language));
+    Some(path)
+}
+
+// Runs of letters, keeping apostrophes inside a word like "don't"
+pub fn word_ranges(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, &c) in chars.iter().enumerate() {
+        let inner_apostrophe = c == '\''
+            && start.is_some()
+            && chars.get(i + 1).is_some_and(|next| next.is_alphabetic());
+        match (c.is_alphabetic() || inner_apostrophe, start) {
+            (true, None) => start = Some(i),
+            (false, Some(begin)) => {
+                ranges.push((begin, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        ranges.push((begin, chars.len()));
+    }
+    ranges
+}
+
+pub struct SuggestionPicker {
+    word: String,
+    suggestions: Vec<String>,
+    selected: usize,
+}
+
+impl SuggestionPicker {
+    pub fn new(word: String, suggestions: Vec<String>) -> Self {
+        Self {
+            word,
+            suggestions,
+            selected: 0,
+        }
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.suggestions.get(self.selected).map(String::as_str)
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.suggestions.len().saturating_sub(1));
+    }
+}
+
+impl Widget for &SuggestionPicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = if self.suggestions.is_empty() {
+            vec![Line::from("No suggestions")]
+        } else {
+            self.suggestions
+                .iter()
+                .enumerate()
+                .map(|(i, suggestion)| {
+                    let style = if i == self.selected {
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default()
+                    };
+                    Line::styled(suggestion.clone(), style)
+                })
+                .collect()
+        };
+        let popup = Popup::new(Text::from(lines))
+            .with_title(&format!("Replace {:?} (Enter)", self.word))
+            .with_size(40, 40)
+            .with_wrap(false);
+        let height = popup.inner_height(area) as usize;
+        let scroll = (self.selected + 1).saturating_sub(height);
+        popup.with_scroll(scroll as u16).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> Spellchecker {
+        Spellchecker::new(["the", "cat", "sat", "don't", "cart"], ["ticktui"])
+    }
+
+    #[test]
+    fn flags_unknown_words_only() {
+        let checker = checker();
+        assert_eq!(checker.misspelled("The cat szt, TickTui"), [(8, 11)]);
+        assert!(checker.misspelled("don't 42 cat").is_empty());
+    }
+
+    #[test]
+    fn suggests_words_one_edit_away() {
+        let checker = checker();
+        assert_eq!(checker.suggestions("cta"), ["cat"]);
+        assert_eq!(checker.suggestions("cat"), ["cart", "sat"]);
+    }
+
+    #[test]
+    fn word_ranges_keep_inner_apostrophes() {
+        let chars: Vec<char> = "'tis don't it's'".chars().collect();
+        assert_eq!(word_ranges(&chars), [(1, 4), (5, 10), (11, 15)]);
+    }
+}
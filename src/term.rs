@@ -21,8 +21,8 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
-const TICK_RATE: f64 = 4.0;
-const FRAME_RATE: f64 = 60.0;
+pub const TICK_RATE: f64 = 4.0;
+pub const FRAME_RATE: f64 = 60.0;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
@@ -46,6 +46,8 @@ pub struct TerminalInterface {
     cancellation_token: CancellationToken,
     event_rx: UnboundedReceiver<Event>,
     event_tx: UnboundedSender<Event>,
+    tick_rate: f64,
+    frame_rate: f64,
 }
 
 impl TerminalInterface {
@@ -60,12 +62,24 @@ impl TerminalInterface {
             cancellation_token,
             event_rx,
             event_tx,
+            tick_rate: TICK_RATE,
+            frame_rate: FRAME_RATE,
         })
     }
 
+    pub fn with_tick_rate(mut self, tick_rate: f64) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    pub fn with_frame_rate(mut self, frame_rate: f64) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
     pub fn start(&mut self) {
-        let tick_delay = std::time::Duration::from_secs_f64(1.0 / TICK_RATE);
-        let render_delay = std::time::Duration::from_secs_f64(1.0 / FRAME_RATE);
+        let tick_delay = std::time::Duration::from_secs_f64(1.0 / self.tick_rate);
+        let render_delay = std::time::Duration::from_secs_f64(1.0 / self.frame_rate);
         self.cancel();
         self.cancellation_token = CancellationToken::new();
         let _cancellation_token = self.cancellation_token.clone();
@@ -183,6 +197,12 @@ impl TerminalInterface {
         Ok(())
     }
 
+    // Forces a full redraw, e.g. after another program has drawn over the screen
+    pub fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         stdout().flush()?;
         Ok(())
@@ -0,0 +1,120 @@
+use chrono::{Local, NaiveTime, Timelike};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::Widget,
+};
+
+use crate::popup::Popup;
+
+const MINUTE_STEP: u32 = 15;
+const PRESETS: [(&str, u32, u32); 4] = [
+    ("Morning", 9, 0),
+    ("Noon", 12, 0),
+    ("Afternoon", 15, 0),
+    ("Evening", 18, 0),
+];
+const PICKER_WIDTH: u16 = 24;
+const PICKER_HEIGHT: u16 = 14;
+
+#[derive(Debug, Clone, Copy)]
+pub enum TimePickerAction {
+    Move(isize),
+    Top,
+    Bottom,
+}
+
+struct TimeEntry {
+    label: String,
+    time: NaiveTime,
+}
+
+pub struct TimePicker {
+    entries: Vec<TimeEntry>,
+    selected: usize,
+    format: String,
+}
+
+impl TimePicker {
+    // Starts on the time already in the field, or the next quarter hour if it doesn't parse
+    pub fn new(text: &str, format: &str) -> Self {
+        let presets = PRESETS.iter().filter_map(|(name, hour, minute)| {
+            let time = NaiveTime::from_hms_opt(*hour, *minute, 0)?;
+            Some(TimeEntry {
+                label: format!("{:<10}{}", name, time.format(format)),
+                time,
+            })
+        });
+        let steps = (0..24 * 60 / MINUTE_STEP).filter_map(|step| {
+            let minutes = step * MINUTE_STEP;
+            let time = NaiveTime::from_hms_opt(minutes / 60, minutes % 60, 0)?;
+            Some(TimeEntry {
+                label: time.format(format).to_string(),
+                time,
+            })
+        });
+        let entries: Vec<TimeEntry> = presets.chain(steps).collect();
+
+        let initial = NaiveTime::parse_from_str(text.trim(), format).unwrap_or_else(|_| {
+            let now = Local::now().time();
+            let minutes = (now.hour() * 60 + now.minute()).div_ceil(MINUTE_STEP) * MINUTE_STEP;
+            NaiveTime::from_hms_opt(minutes / 60 % 24, minutes % 60, 0).unwrap_or_default()
+        });
+        let selected = entries
+            .iter()
+            .skip(PRESETS.len())
+            .position(|entry| entry.time >= initial)
+            .map_or(entries.len() - 1, |index| index + PRESETS.len());
+        Self {
+            entries,
+            selected,
+            format: format.to_string(),
+        }
+    }
+
+    // The selected time in the field's format
+    pub fn formatted(&self) -> String {
+        self.entries[self.selected]
+            .time
+            .format(&self.format)
+            .to_string()
+    }
+
+    pub fn apply(&mut self, action: TimePickerAction) {
+        let last = self.entries.len() - 1;
+        self.selected = match action {
+            TimePickerAction::Move(delta) => self.selected.saturating_add_signed(delta).min(last),
+            TimePickerAction::Top => 0,
+            TimePickerAction::Bottom => last,
+        };
+    }
+}
+
+impl Widget for &TimePicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let style = if i == self.selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                Line::styled(entry.label.clone(), style)
+            })
+            .collect();
+        let popup = Popup::new(Text::from(lines))
+            .with_title("Pick a time")
+            .with_fixed_size(PICKER_WIDTH, PICKER_HEIGHT)
+            .with_wrap(false);
+        // Keep the selection in the middle of the list where possible
+        let height = popup.inner_height(area) as usize;
+        let max_scroll = self.entries.len().saturating_sub(height);
+        let scroll = self.selected.saturating_sub(height / 2).min(max_scroll);
+        popup.with_scroll(scroll as u16).render(area, buf);
+    }
+}
@@ -1,20 +1,342 @@
 use anyhow::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Position, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Paragraph},
 };
 use tui_textarea::{CursorMove, Input};
+
 // use tui_text::{EditorEventHandler, EditorMode, EditorState, EditorTheme, EditorView};
 
-enum ActiveWidget {
+use crate::{
+    config::{Config, DateConfig, LayoutConfig},
+    datepicker::{DatePicker, DatePickerAction},
+    links::{LinkPicker, find_urls},
+    popup::Popup,
+    spelling::{Spellchecker, SuggestionPicker},
+    timepicker::{TimePicker, TimePickerAction},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveWidget {
     MainEditor,
     CompositeEditor,
     // TaskList,
     // Popup,
 }
 
+// Every change to UI state goes through one of these, sent over the app's action channel
+#[derive(Debug, Clone)]
+pub enum UiAction {
+    CycleLayout,
+    StartWindowCommand,
+    CancelWindowCommand,
+    ToggleZoom,
+    Focus(ActiveWidget),
+    Edit(ActiveWidget, EditorAction),
+    CancelPending(ActiveWidget),
+    Click(Position),
+    Scroll(Position, i16),
+    OpenHelp,
+    OpenLogViewer,
+    CloseOverlay,
+    ScrollOverlay(i16),
+    ScrollOverlayToEnd,
+    SubmitOverlay,
+    OpenPicker(FieldPicker),
+    DatePicker(DatePickerAction),
+    TimePicker(TimePickerAction),
+    OpenLinkPicker(ActiveWidget),
+    OpenRegisters(ActiveWidget),
+    OpenSpellingSuggestions,
+    Save,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayoutPreset {
+    ThreePane,
+    EditorsOnly,
+    MainEditor,
+    CompositeEditor,
+}
+
+impl LayoutPreset {
+    fn next(self) -> Self {
+        match self {
+            Self::ThreePane => Self::EditorsOnly,
+            Self::EditorsOnly => Self::MainEditor,
+            Self::MainEditor => Self::CompositeEditor,
+            Self::CompositeEditor => Self::ThreePane,
+        }
+    }
+
+    fn constraints(self, ratios: [u16; 3]) -> [Constraint; 3] {
+        let [content, main, composite] = ratios;
+        match self {
+            Self::ThreePane => [
+                Constraint::Fill(content),
+                Constraint::Fill(main),
+                Constraint::Fill(composite),
+            ],
+            // Fall back to an even split if both editor ratios are zero
+            Self::EditorsOnly if main == 0 && composite == 0 => [
+                Constraint::Length(0),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+            Self::EditorsOnly => [
+                Constraint::Length(0),
+                Constraint::Fill(main),
+                Constraint::Fill(composite),
+            ],
+            Self::MainEditor => [
+                Constraint::Length(0),
+                Constraint::Fill(1),
+                Constraint::Length(0),
+            ],
+            Self::CompositeEditor => [
+                Constraint::Length(0),
+                Constraint::Length(0),
+                Constraint::Fill(1),
+            ],
+        }
+    }
+}
+
+const LOG_VIEWER_LINES: usize = 500;
+
+const HELP_TEXT: &str = "Global
+  F2        Cycle layout
+  C-w z     Zoom the active pane, again to restore (Normal mode)
+  F12       Show logs
+  C-e       Edit the active field in $EDITOR (Normal mode)
+  C-s       Save the fields, blocked while one is invalid
+  ?         Show this help (Normal mode)
+  q         Quit (Normal mode, outside the fields)
+  Ctrl+C    Quit
+
+Editor
+  Enter     Open the field's picker (Normal mode)
+  h/j/k/l   Move
+  w/b/e     Word motions
+  f/t/F/T   Find character, repeat with ; and ,
+  /, n, N   Search
+  i/a/I/A   Insert
+  R         Replace
+  v/V/C-v   Visual, visual line, visual block
+  d/c/y     Delete, change, yank
+  p         Paste
+  u/C-r     Undo, redo
+  .         Repeat last change
+  gq        Reflow to the text width: gqq, gqap, or a selection
+  gx        Pick a link in the text to open
+  g\"        Show registers
+  zl/zh     Scroll right/left
+  zw        Toggle soft wrap
+  z=        Suggest spellings for the underlined word (when enabled)
+  q{a-z}    Record macro in the fields, q to stop, @{a-z} to play
+  m{a-z}    Set mark, `{a-z} to jump
+
+Popups
+  j/k       Scroll
+  Esc/q     Close
+
+Date picker
+  h/l       Previous/next day
+  j/k       Next/previous week
+  H/L       Previous/next month
+  t/T/w     Today, tomorrow, a week from today
+  Enter     Use the selected date
+
+Time picker
+  j/k       Next/previous time
+  C-d/C-u   Two hours later/earlier
+  g/G       First preset, last time
+  Enter     Use the selected time";
+
+struct LogViewer {
+    lines: Vec<String>,
+    scroll: usize,
+}
+
+impl LogViewer {
+    fn new() -> Self {
+        Self {
+            lines: crate::logging::read_recent(LOG_VIEWER_LINES),
+            scroll: 0,
+        }
+    }
+
+    // Returns whether the log contents changed
+    fn refresh(&mut self) -> bool {
+        let lines = crate::logging::read_recent(LOG_VIEWER_LINES);
+        if lines == self.lines {
+            return false;
+        }
+        self.lines = lines;
+        self.scroll = self.scroll.min(self.lines.len());
+        true
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect) {
+        let popup = Popup::new(self.lines.join("\n"))
+            .with_title("Logs (j/k: Scroll | G: Latest | Esc: Close)")
+            .with_size(80, 70)
+            .with_wrap(false);
+        let height = popup.inner_height(area) as usize;
+        let top = self.lines.len().saturating_sub(height + self.scroll);
+        f.render_widget(popup.with_scroll(top as u16), area);
+    }
+}
+
+// Popups layered over the panes; the topmost one captures all input until it is closed
+enum Overlay {
+    Help { scroll: usize },
+    Logs(LogViewer),
+    DatePicker(DatePicker),
+    TimePicker(TimePicker),
+    LinkPicker(LinkPicker),
+    Registers { lines: Vec<String>, scroll: usize },
+    Suggestions(SuggestionPicker),
+}
+
+impl Overlay {
+    // Positive deltas scroll down, towards the end of the content
+    fn scroll_by(&mut self, delta: i16) {
+        match self {
+            Overlay::Help { scroll } => {
+                *scroll = scroll
+                    .saturating_add_signed(delta as isize)
+                    .min(HELP_TEXT.lines().count().saturating_sub(1));
+            }
+            Overlay::Logs(log_viewer) => {
+                log_viewer.scroll = log_viewer
+                    .scroll
+                    .saturating_add_signed(-delta as isize)
+                    .min(log_viewer.lines.len());
+            }
+            Overlay::DatePicker(_) => {}
+            Overlay::TimePicker(time_picker) => {
+                time_picker.apply(TimePickerAction::Move(delta as isize))
+            }
+            Overlay::LinkPicker(link_picker) => link_picker.move_by(delta as isize),
+            Overlay::Suggestions(picker) => picker.move_by(delta as isize),
+            Overlay::Registers { lines, scroll } => {
+                *scroll = scroll
+                    .saturating_add_signed(delta as isize)
+                    .min(lines.len().saturating_sub(1));
+            }
+        }
+    }
+
+    fn scroll_to_end(&mut self) {
+        match self {
+            Overlay::Help { .. } | Overlay::DatePicker(_) => {}
+            Overlay::Logs(log_viewer) => log_viewer.scroll = 0,
+            Overlay::LinkPicker(link_picker) => link_picker.move_by(isize::MAX),
+            Overlay::Suggestions(picker) => picker.move_by(isize::MAX),
+            Overlay::TimePicker(time_picker) => time_picker.apply(TimePickerAction::Bottom),
+            Overlay::Registers { lines, scroll } => *scroll = lines.len().saturating_sub(1),
+        }
+    }
+
+    fn refresh(&mut self) -> bool {
+        match self {
+            Overlay::Help { .. }
+            | Overlay::DatePicker(_)
+            | Overlay::TimePicker(_)
+            | Overlay::LinkPicker(_)
+            | Overlay::Registers { .. }
+            | Overlay::Suggestions(_) => false,
+            Overlay::Logs(log_viewer) => log_viewer.refresh(),
+        }
+    }
+
+    fn map_key_event(&self, event: KeyEvent) -> Option<UiAction> {
+        match (self, event.code) {
+            (_, KeyCode::Esc | KeyCode::Char('q')) => Some(UiAction::CloseOverlay),
+            (Overlay::Help { .. }, KeyCode::Char('?')) => Some(UiAction::CloseOverlay),
+            (Overlay::Logs(_), KeyCode::F(12)) => Some(UiAction::CloseOverlay),
+            (Overlay::DatePicker(_), code) => map_date_picker_key(code),
+            (Overlay::TimePicker(_), _) => map_time_picker_key(event),
+            (Overlay::LinkPicker(_) | Overlay::Suggestions(_), KeyCode::Enter) => {
+                Some(UiAction::SubmitOverlay)
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) => Some(UiAction::ScrollOverlay(1)),
+            (_, KeyCode::Char('k') | KeyCode::Up) => Some(UiAction::ScrollOverlay(-1)),
+            (_, KeyCode::Char('G')) => Some(UiAction::ScrollOverlayToEnd),
+            _ => None,
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect) {
+        match self {
+            Overlay::Help { scroll } => {
+                let popup = Popup::new(HELP_TEXT)
+                    .with_title("Help (j/k: Scroll | Esc: Close)")
+                    .with_size(60, 70)
+                    .with_scroll(*scroll as u16);
+                f.render_widget(popup, area);
+            }
+            Overlay::Logs(log_viewer) => log_viewer.render(f, area),
+            Overlay::DatePicker(date_picker) => f.render_widget(date_picker, area),
+            Overlay::TimePicker(time_picker) => f.render_widget(time_picker, area),
+            Overlay::LinkPicker(link_picker) => f.render_widget(link_picker, area),
+            Overlay::Suggestions(picker) => f.render_widget(picker, area),
+            Overlay::Registers { lines, scroll } => {
+                let text = if lines.is_empty() {
+                    "Nothing yanked or deleted yet".to_string()
+                } else {
+                    lines.join("\n")
+                };
+                let popup = Popup::new(text)
+                    .with_title("Registers (j/k: Scroll | Esc: Close)")
+                    .with_size(60, 50)
+                    .with_wrap(false)
+                    .with_scroll(*scroll as u16);
+                f.render_widget(popup, area);
+            }
+        }
+    }
+}
+
+fn map_date_picker_key(code: KeyCode) -> Option<UiAction> {
+    let action = match code {
+        KeyCode::Enter => return Some(UiAction::SubmitOverlay),
+        KeyCode::Char('h') | KeyCode::Left => DatePickerAction::Days(-1),
+        KeyCode::Char('l') | KeyCode::Right => DatePickerAction::Days(1),
+        KeyCode::Char('j') | KeyCode::Down => DatePickerAction::Days(7),
+        KeyCode::Char('k') | KeyCode::Up => DatePickerAction::Days(-7),
+        KeyCode::Char('H') | KeyCode::PageUp => DatePickerAction::Months(-1),
+        KeyCode::Char('L') | KeyCode::PageDown => DatePickerAction::Months(1),
+        KeyCode::Char('t') => DatePickerAction::Today,
+        KeyCode::Char('T') => DatePickerAction::Tomorrow,
+        KeyCode::Char('w') => DatePickerAction::NextWeek,
+        _ => return None,
+    };
+    Some(UiAction::DatePicker(action))
+}
+
+fn map_time_picker_key(event: KeyEvent) -> Option<UiAction> {
+    let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+    let action = match event.code {
+        KeyCode::Enter => return Some(UiAction::SubmitOverlay),
+        KeyCode::Char('d') if ctrl => TimePickerAction::Move(8),
+        KeyCode::Char('u') if ctrl => TimePickerAction::Move(-8),
+        KeyCode::Char('j') | KeyCode::Down => TimePickerAction::Move(1),
+        KeyCode::Char('k') | KeyCode::Up => TimePickerAction::Move(-1),
+        KeyCode::PageDown => TimePickerAction::Move(8),
+        KeyCode::PageUp => TimePickerAction::Move(-8),
+        KeyCode::Char('g') | KeyCode::Home => TimePickerAction::Top,
+        KeyCode::Char('G') | KeyCode::End => TimePickerAction::Bottom,
+        _ => return None,
+    };
+    Some(UiAction::TimePicker(action))
+}
+
 const EDITOR_CONTENT: &str = r#"fn factorial(n: u32) -> u32 {
     if n == 0 {
         1
@@ -29,8 +351,8 @@ fn main() {
 }"#;
 
 use crate::editor::{
-    CompositeEditor, Editor, EditorAction, EditorActions, EditorMode, EditorStyle, handle_input,
-    handle_pending_action_input,
+    CompositeEditor, Editor, EditorAction, EditorActions, EditorMode, EditorStyle, FieldPicker,
+    date_validator, handle_input, handle_pending_action_input, time_validator,
 };
 
 pub struct UserInterface {
@@ -39,24 +361,45 @@ pub struct UserInterface {
     composite_editor: CompositeEditor,
     current_area: Option<Rect>,
     active_widget: Option<ActiveWidget>,
+    layout: LayoutPreset,
+    zoomed: bool,
+    window_pending: bool,
+    too_small: bool,
+    dates: DateConfig,
+    layout_config: LayoutConfig,
+    overlays: Vec<Overlay>,
 }
 
 impl UserInterface {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
+        let dates = config.dates.clone();
+        let (date_example, time_example) = (dates.date_example(), dates.time_example());
+        let text_width = config.editor.text_width;
         #[rustfmt::skip]
         let composite_editors = vec![
             Editor::default()
-                .with_title("Editor 1")
+                .with_title("Date")
+                .with_single_line(true)
+                .with_placeholder(&date_example)
+                .with_validator(date_validator(&dates.date_format, &date_example))
+                .with_picker(FieldPicker::Date),
+            Editor::default()
+                .with_title("Time")
                 .with_single_line(true)
-                .with_placeholder("Single line editor"),
+                .with_placeholder(&time_example)
+                .with_validator(time_validator(dates.time_format(), &time_example))
+                .with_picker(FieldPicker::Time),
             Editor::default()
                 .with_title("Editor 2")
-                .with_content(EDITOR_CONTENT),
+                .with_content(EDITOR_CONTENT)
+                .with_text_width(text_width),
             Editor::default()
                 .with_title("Editor 3")
-                .with_content(EDITOR_CONTENT),
+                .with_content(EDITOR_CONTENT)
+                .with_text_width(text_width),
         ];
         let constraints = vec![
+            Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Min(3),
             Constraint::Min(3),
@@ -66,6 +409,9 @@ impl UserInterface {
         let editor = Editor::default()
             .with_title("Main Editor")
             .with_content(EDITOR_CONTENT)
+            .with_text_width(text_width)
+            .with_wrap(config.editor.wrap)
+            .with_spellchecker(Spellchecker::load(&config.spellcheck))
             .with_block(
                 Block::default()
                     .title("Main Editor")
@@ -78,6 +424,13 @@ impl UserInterface {
             composite_editor,
             current_area: None,
             active_widget: Some(ActiveWidget::MainEditor),
+            layout: LayoutPreset::ThreePane,
+            zoomed: false,
+            window_pending: false,
+            too_small: false,
+            dates,
+            layout_config: config.layout,
+            overlays: Vec::new(),
         }
     }
 
@@ -89,6 +442,68 @@ impl UserInterface {
         self.composite_editor.get_mode()
     }
 
+    pub fn has_pending_action(&mut self) -> bool {
+        if self.window_pending {
+            return true;
+        }
+        match self.active_widget {
+            Some(ActiveWidget::MainEditor) => self.editor.get_pending_action().is_some(),
+            Some(ActiveWidget::CompositeEditor) => {
+                self.composite_editor.get_pending_action().is_some()
+            }
+            None => false,
+        }
+    }
+
+    // The composite editor records macros with q{a-z} ... q in normal mode, so q only quits
+    // from elsewhere
+    pub fn q_records_macros(&mut self) -> bool {
+        self.active_widget == Some(ActiveWidget::CompositeEditor)
+            && self.active_editor_mode() == Some(EditorMode::Normal)
+    }
+
+    pub fn has_overlay(&self) -> bool {
+        !self.overlays.is_empty()
+    }
+
+    // Returns whether anything changed that needs a redraw
+    pub fn tick(&mut self) -> bool {
+        // Every overlay refreshes, so no short-circuiting here
+        let mut changed = false;
+        for overlay in self.overlays.iter_mut() {
+            changed |= overlay.refresh();
+        }
+        changed
+    }
+
+    pub fn get_active_text(&self) -> Option<String> {
+        match self.active_widget {
+            Some(ActiveWidget::MainEditor) => Some(self.editor.get_lines().join("\n")),
+            Some(ActiveWidget::CompositeEditor) => self.composite_editor.get_active_text(),
+            None => None,
+        }
+    }
+
+    pub fn set_active_text(&mut self, text: &str) {
+        match self.active_widget {
+            Some(ActiveWidget::MainEditor) => self.editor.set_text(text),
+            Some(ActiveWidget::CompositeEditor) => self.composite_editor.set_active_text(text),
+            None => {}
+        }
+    }
+
+    pub fn active_editor_mode(&mut self) -> Option<EditorMode> {
+        match self.active_widget {
+            Some(ActiveWidget::MainEditor) => Some(self.editor.get_mode()),
+            Some(ActiveWidget::CompositeEditor) => self.composite_editor.get_mode(),
+            None => None,
+        }
+    }
+
+    fn push_overlay(&mut self, overlay: Overlay) {
+        self.overlays.push(overlay);
+    }
+
     fn set_active_widget(&mut self, widget: ActiveWidget) {
         match widget {
             ActiveWidget::MainEditor => {
@@ -104,80 +519,270 @@ impl UserInterface {
         }
     }
 
-    pub fn handle_key_event_main_editor(&mut self, event: KeyEvent) {
+    fn cycle_layout(&mut self) {
+        self.layout = self.layout.next();
+        tracing::debug!(layout = ?self.layout, "cycled layout");
+        match self.layout {
+            LayoutPreset::MainEditor => self.set_active_widget(ActiveWidget::MainEditor),
+            LayoutPreset::CompositeEditor => self.set_active_widget(ActiveWidget::CompositeEditor),
+            LayoutPreset::ThreePane | LayoutPreset::EditorsOnly => {}
+        }
+    }
+
+    // Zoomed or narrow terminals show only the active pane, whatever the chosen preset
+    fn visible_layout(&self, width: u16) -> LayoutPreset {
+        if !self.zoomed && width >= self.layout_config.narrow_width {
+            return self.layout;
+        }
+        match self.active_widget {
+            Some(ActiveWidget::CompositeEditor) => LayoutPreset::CompositeEditor,
+            Some(ActiveWidget::MainEditor) | None => LayoutPreset::MainEditor,
+        }
+    }
+
+    fn map_key_event_main_editor(&mut self, event: KeyEvent) -> Option<UiAction> {
         let input: Input = event.into();
         let mode = self.editor.get_mode();
         let action_opt = if let Some(pending_action) = self.editor.get_pending_action() {
             match handle_pending_action_input(input, pending_action) {
                 Some(action) => Some(action),
-                None => {
-                    self.editor.set_pending_action(None);
-                    None
-                }
+                None => return Some(UiAction::CancelPending(ActiveWidget::MainEditor)),
             }
         } else {
             handle_input(input, mode)
         };
-        match action_opt {
-            Some(action) => match action {
-                EditorAction::ApplyInput(_) => self.editor.execute_action(action),
-                EditorAction::MoveCursor(mvmt) => match mvmt {
-                    CursorMove::Right if self.editor.is_cursor_at_line_end() => {
-                        self.set_active_widget(ActiveWidget::CompositeEditor);
-                    }
-                    _ => self.editor.execute_action(action),
-                },
-                _ => self.editor.execute_action(action),
-            },
-            None => {}
+        match action_opt? {
+            EditorAction::MoveCursor(CursorMove::Right) if self.editor.is_cursor_at_line_end() => {
+                Some(UiAction::Focus(ActiveWidget::CompositeEditor))
+            }
+            EditorAction::OpenLinks => Some(UiAction::OpenLinkPicker(ActiveWidget::MainEditor)),
+            EditorAction::ShowRegisters => Some(UiAction::OpenRegisters(ActiveWidget::MainEditor)),
+            EditorAction::SuggestSpelling => Some(UiAction::OpenSpellingSuggestions),
+            action => Some(UiAction::Edit(ActiveWidget::MainEditor, action)),
         }
     }
 
-    pub fn handle_key_event_composite_editor(&mut self, event: KeyEvent) {
+    fn map_key_event_composite_editor(&mut self, event: KeyEvent) -> Option<UiAction> {
         let input: Input = event.into();
-        if let Some(mode) = self.composite_editor.get_mode() {
-            let action_opt =
-                if let Some(pending_action) = self.composite_editor.get_pending_action() {
-                    match handle_pending_action_input(input, pending_action) {
-                        Some(action) => Some(action),
-                        None => {
-                            self.composite_editor.set_pending_action(None);
-                            None
-                        }
-                    }
+        let mode = self.composite_editor.get_mode()?;
+        if event.code == KeyCode::Enter
+            && mode == EditorMode::Normal
+            && self.composite_editor.get_pending_action().is_none()
+            && let Some(picker) = self.composite_editor.get_active_picker()
+        {
+            return Some(UiAction::OpenPicker(picker));
+        }
+        let action_opt = if let Some(pending_action) = self.composite_editor.get_pending_action() {
+            match handle_pending_action_input(input, pending_action) {
+                Some(action) => Some(action),
+                None => return Some(UiAction::CancelPending(ActiveWidget::CompositeEditor)),
+            }
+        } else {
+            handle_input(input, mode)
+        };
+        match action_opt? {
+            EditorAction::MoveCursor(CursorMove::Left)
+                if self.composite_editor.is_cursor_at_line_start() =>
+            {
+                Some(UiAction::Focus(ActiveWidget::MainEditor))
+            }
+            EditorAction::OpenLinks => {
+                Some(UiAction::OpenLinkPicker(ActiveWidget::CompositeEditor))
+            }
+            EditorAction::ShowRegisters => {
+                Some(UiAction::OpenRegisters(ActiveWidget::CompositeEditor))
+            }
+            action => Some(UiAction::Edit(ActiveWidget::CompositeEditor, action)),
+        }
+    }
+
+    // Translates a key event into the UI action it triggers without changing any state
+    pub fn map_key_event(&mut self, event: KeyEvent) -> Option<UiAction> {
+        // Keys would edit text the user can't see; the app still handles quitting
+        if self.too_small {
+            return None;
+        }
+        if let Some(overlay) = self.overlays.last() {
+            return overlay.map_key_event(event);
+        }
+        if self.window_pending {
+            return match event.code {
+                KeyCode::Char('z') => Some(UiAction::ToggleZoom),
+                _ => Some(UiAction::CancelWindowCommand),
+            };
+        }
+        match event.code {
+            KeyCode::F(2) => return Some(UiAction::CycleLayout),
+            // Insert mode leaves Ctrl+W to the editor to delete a word
+            KeyCode::Char('w')
+                if event.modifiers.contains(KeyModifiers::CONTROL)
+                    && !self.has_pending_action()
+                    && self.active_editor_mode() == Some(EditorMode::Normal) =>
+            {
+                return Some(UiAction::StartWindowCommand);
+            }
+            KeyCode::F(12) => return Some(UiAction::OpenLogViewer),
+            KeyCode::Char('s') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(UiAction::Save);
+            }
+            KeyCode::Char('?')
+                if !self.has_pending_action()
+                    && self.active_editor_mode() == Some(EditorMode::Normal) =>
+            {
+                return Some(UiAction::OpenHelp);
+            }
+            _ => {}
+        }
+        match self.active_widget {
+            Some(ActiveWidget::MainEditor) => self.map_key_event_main_editor(event),
+            Some(ActiveWidget::CompositeEditor) => self.map_key_event_composite_editor(event),
+            // Some(ActiveWidget::TaskList) => None,
+            None => None,
+        }
+    }
+
+    pub fn execute_action(&mut self, action: UiAction) {
+        match action {
+            UiAction::CycleLayout => self.cycle_layout(),
+            UiAction::StartWindowCommand => self.window_pending = true,
+            UiAction::CancelWindowCommand => self.window_pending = false,
+            UiAction::ToggleZoom => {
+                self.window_pending = false;
+                self.zoomed = !self.zoomed;
+            }
+            UiAction::Focus(widget) => self.set_active_widget(widget),
+            UiAction::Edit(ActiveWidget::MainEditor, action) => self.editor.execute_action(action),
+            UiAction::Edit(ActiveWidget::CompositeEditor, action) => {
+                self.composite_editor.execute_action(action)
+            }
+            UiAction::CancelPending(ActiveWidget::MainEditor) => {
+                self.editor.set_pending_action(None)
+            }
+            UiAction::CancelPending(ActiveWidget::CompositeEditor) => {
+                self.composite_editor.set_pending_action(None)
+            }
+            UiAction::Click(_) | UiAction::Scroll(..) if self.too_small => {}
+            UiAction::Click(_) if self.has_overlay() => {}
+            UiAction::Click(pos) => self.handle_mouse_click(pos),
+            UiAction::Scroll(_, delta) if self.has_overlay() => {
+                self.execute_action(UiAction::ScrollOverlay(delta))
+            }
+            UiAction::Scroll(pos, delta) => self.handle_mouse_scroll(pos, delta),
+            UiAction::OpenHelp => self.push_overlay(Overlay::Help { scroll: 0 }),
+            UiAction::OpenLogViewer => self.push_overlay(Overlay::Logs(LogViewer::new())),
+            UiAction::CloseOverlay => {
+                self.overlays.pop();
+            }
+            UiAction::ScrollOverlay(delta) => {
+                if let Some(overlay) = self.overlays.last_mut() {
+                    overlay.scroll_by(delta);
+                }
+            }
+            UiAction::ScrollOverlayToEnd => {
+                if let Some(overlay) = self.overlays.last_mut() {
+                    overlay.scroll_to_end();
+                }
+            }
+            UiAction::SubmitOverlay => self.submit_overlay(),
+            UiAction::OpenPicker(FieldPicker::Date) => {
+                let text = self.composite_editor.get_active_text().unwrap_or_default();
+                let date_picker =
+                    DatePicker::new(&text, &self.dates.date_format, self.dates.week_start);
+                self.push_overlay(Overlay::DatePicker(date_picker));
+            }
+            UiAction::OpenPicker(FieldPicker::Time) => {
+                let text = self.composite_editor.get_active_text().unwrap_or_default();
+                let time_picker = TimePicker::new(&text, self.dates.time_format());
+                self.push_overlay(Overlay::TimePicker(time_picker));
+            }
+            UiAction::OpenLinkPicker(widget) => {
+                self.execute_action(UiAction::CancelPending(widget));
+                let text = match widget {
+                    ActiveWidget::MainEditor => self.editor.get_lines().join("\n"),
+                    ActiveWidget::CompositeEditor => self.composite_editor.get_text(),
+                };
+                let urls = find_urls(&text);
+                if urls.is_empty() {
+                    tracing::debug!("no links to open");
                 } else {
-                    handle_input(input, mode)
+                    self.push_overlay(Overlay::LinkPicker(LinkPicker::new(urls)));
+                }
+            }
+            UiAction::OpenRegisters(widget) => {
+                self.execute_action(UiAction::CancelPending(widget));
+                let lines = match widget {
+                    ActiveWidget::MainEditor => self.editor.register_lines(),
+                    ActiveWidget::CompositeEditor => self.composite_editor.register_lines(),
                 };
-            match action_opt {
-                Some(action) => match action {
-                    EditorAction::ApplyInput(_) => self.composite_editor.execute_action(action),
-                    EditorAction::MoveCursor(mvmt) => match mvmt {
-                        CursorMove::Left if self.composite_editor.is_cursor_at_line_start() => {
-                            self.set_active_widget(ActiveWidget::MainEditor);
-                        }
-                        _ => self.composite_editor.execute_action(action),
-                    },
-                    _ => self.composite_editor.execute_action(action),
-                },
-                None => {}
+                self.push_overlay(Overlay::Registers { lines, scroll: 0 });
+            }
+            // There is no task backend yet, so saving only checks the fields and marks them clean
+            UiAction::Save => match self.composite_editor.save() {
+                Ok(()) => tracing::info!("saved the fields"),
+                Err(error) => {
+                    tracing::warn!("not saved, {}", error);
+                    self.set_active_widget(ActiveWidget::CompositeEditor);
+                }
+            },
+            UiAction::OpenSpellingSuggestions => {
+                self.execute_action(UiAction::CancelPending(ActiveWidget::MainEditor));
+                match self.editor.spelling_suggestions() {
+                    Some((word, suggestions)) => self.push_overlay(Overlay::Suggestions(
+                        SuggestionPicker::new(word, suggestions),
+                    )),
+                    None => tracing::debug!("no misspelled word under the cursor"),
+                }
+            }
+            UiAction::TimePicker(action) => {
+                if let Some(Overlay::TimePicker(time_picker)) = self.overlays.last_mut() {
+                    time_picker.apply(action);
+                }
+            }
+            UiAction::DatePicker(action) => {
+                if let Some(Overlay::DatePicker(date_picker)) = self.overlays.last_mut() {
+                    date_picker.apply(action);
+                }
             }
         }
     }
 
-    pub fn handle_key_event(&mut self, event: KeyEvent) {
-        match self.active_widget {
-            Some(ActiveWidget::MainEditor) => self.handle_key_event_main_editor(event),
-            Some(ActiveWidget::CompositeEditor) => self.handle_key_event_composite_editor(event),
-            // Some(ActiveWidget::TaskList) => {}
-            None => {}
+    // Closes the topmost overlay, writing any picked value back into the active field
+    fn submit_overlay(&mut self) {
+        match self.overlays.pop() {
+            Some(Overlay::DatePicker(date_picker)) => {
+                self.composite_editor
+                    .set_active_text(&date_picker.formatted());
+            }
+            Some(Overlay::TimePicker(time_picker)) => {
+                self.composite_editor
+                    .set_active_text(&time_picker.formatted());
+            }
+            Some(Overlay::LinkPicker(link_picker)) => {
+                if let Some(url) = link_picker.selected() {
+                    tracing::info!("opening {}", url);
+                    if let Err(e) = open::that_detached(url) {
+                        tracing::error!("failed to open {}: {}", url, e);
+                    }
+                }
+            }
+            Some(Overlay::Suggestions(picker)) => {
+                if let Some(replacement) = picker.selected() {
+                    self.editor.replace_misspelled_word(replacement);
+                }
+            }
+            Some(Overlay::Help { .. } | Overlay::Logs(_) | Overlay::Registers { .. }) | None => {}
         }
     }
 
-    pub fn handle_mouse_click(&mut self, pos: Position) {
+    fn handle_mouse_click(&mut self, pos: Position) {
         if let Some(area) = &self.current_area {
             let main_chunks = main_chunks(area.clone());
             if main_chunks[0].contains(pos) {
-                let content_chunks = content_chunks(main_chunks[0]);
+                let content_chunks = content_chunks(
+                    main_chunks[0],
+                    self.visible_layout(area.width),
+                    &self.layout_config,
+                );
                 if content_chunks[1].contains(pos) {
                     self.set_active_widget(ActiveWidget::MainEditor);
                     let local_pos = Position {
@@ -197,16 +802,70 @@ impl UserInterface {
         }
     }
 
+    fn handle_mouse_scroll(&mut self, pos: Position, delta: i16) {
+        if let Some(area) = &self.current_area {
+            let main_chunks = main_chunks(*area);
+            if main_chunks[0].contains(pos) {
+                let content_chunks = content_chunks(
+                    main_chunks[0],
+                    self.visible_layout(area.width),
+                    &self.layout_config,
+                );
+                if content_chunks[1].contains(pos) {
+                    self.editor.on_scroll(delta);
+                } else if content_chunks[2].contains(pos) {
+                    self.composite_editor.on_scroll(pos, delta);
+                }
+            }
+        }
+    }
+
     pub fn draw(&mut self, f: &mut Frame, area: Rect) -> Result<()> {
-        let main_chunks = main_chunks(area);
-        self.current_area = Some(area.clone());
-        let content_chunks = content_chunks(main_chunks[0]);
+        let capped_area = capped_area(area, &self.layout_config);
+        self.too_small = capped_area.width < self.layout_config.min_width
+            || capped_area.height < self.layout_config.min_height;
+        if self.too_small {
+            // Nothing is clickable while the placeholder is up
+            self.current_area = None;
+            self.render_too_small(f, area);
+            return Ok(());
+        }
+        let main_chunks = main_chunks(capped_area);
+        self.current_area = Some(capped_area);
+        let content_chunks = content_chunks(
+            main_chunks[0],
+            self.visible_layout(capped_area.width),
+            &self.layout_config,
+        );
         // self.render_header(f, main_chunks[0]);
         self.render_content(f, content_chunks)?;
         self.render_footer(f, main_chunks[1])?;
+        for overlay in &self.overlays {
+            overlay.render(f, area);
+        }
         Ok(())
     }
 
+    fn render_too_small(&self, f: &mut Frame, area: Rect) {
+        let message = vec![
+            Line::from("Terminal too small"),
+            Line::from(format!(
+                "{}x{}, need {}x{}",
+                area.width,
+                area.height,
+                self.layout_config.min_width,
+                self.layout_config.min_height
+            )),
+        ];
+        let [message_area] = Layout::vertical([Constraint::Length(2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let placeholder = Paragraph::new(message)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(placeholder, message_area);
+    }
+
     // fn render_header(&self, f: &mut Frame, area: Rect) {
     //     let style = Style::default().fg(Color::Yellow);
     //     let title = "TickTUI";
@@ -229,12 +888,25 @@ impl UserInterface {
         f.render_widget(&self.editor, areas[1]);
         self.composite_editor.set_last_area(areas[2].clone());
         f.render_widget(&self.composite_editor, areas[2]);
+        // Marks unsaved changes on the first field's top border
+        if self.composite_editor.is_modified() {
+            let marker = Block::default().title(Line::from(" [+] ").right_aligned());
+            f.render_widget(marker, areas[2]);
+        }
         Ok(())
     }
 
     fn render_footer(&self, f: &mut Frame, area: Rect) -> Result<()> {
-        let footer_text = "?: Help | q: Quit";
-        let footer = Paragraph::new(footer_text).alignment(Alignment::Center);
+        let footer_text = "?: Help | F2: Layout | F12: Logs | C-s: Save | q: Quit";
+        let footer = match self.composite_editor.validation_error() {
+            Some(error) => Paragraph::new(Line::from(vec![
+                Span::styled(error, Style::default().fg(Color::LightRed)),
+                Span::raw(" | "),
+                Span::raw(footer_text),
+            ])),
+            None => Paragraph::new(footer_text),
+        }
+        .alignment(Alignment::Center);
         // .block(Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT));
 
         f.render_widget(footer, area);
@@ -250,34 +922,38 @@ fn main_chunks(area: Rect) -> Vec<Rect> {
         .to_vec()
 }
 
-fn content_chunks(area: Rect) -> Vec<Rect> {
-    Layout::default()
+// Always returns the content, main editor and composite editor areas in that order, even when
+// they are drawn right to left
+fn content_chunks(area: Rect, layout: LayoutPreset, config: &LayoutConfig) -> Vec<Rect> {
+    let mut constraints = layout.constraints(config.pane_ratios);
+    if config.reverse_panes {
+        constraints.reverse();
+    }
+    let mut chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(20),
-            Constraint::Percentage(40),
-            Constraint::Percentage(40),
-        ])
+        .constraints(constraints)
         .split(area)
-        .to_vec()
+        .to_vec();
+    if config.reverse_panes {
+        chunks.reverse();
+    }
+    chunks
 }
 
-// fn popup_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-//     let popup_layout = Layout::default()
-//         .direction(ratatui::layout::Direction::Vertical)
-//         .constraints([
-//             Constraint::Percentage((100 - percent_y) / 2),
-//             Constraint::Percentage(percent_y),
-//             Constraint::Percentage((100 - percent_y) / 2),
-//         ])
-//         .split(r);
-
-//     Layout::default()
-//         .direction(ratatui::layout::Direction::Horizontal)
-//         .constraints([
-//             Constraint::Percentage((100 - percent_x) / 2),
-//             Constraint::Percentage(percent_x),
-//             Constraint::Percentage((100 - percent_x) / 2),
-//         ])
-//         .split(popup_layout[1])[1]
-// }
+// Centers the UI within the configured maximum size
+fn capped_area(area: Rect, config: &LayoutConfig) -> Rect {
+    let width = match config.max_width {
+        0 => area.width,
+        max_width => area.width.min(max_width),
+    };
+    let height = match config.max_height {
+        0 => area.height,
+        max_height => area.height.min(max_height),
+    };
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}